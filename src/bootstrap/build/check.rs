@@ -161,6 +161,9 @@ pub fn compiletest(build: &Build,
            .arg("--cflags").arg(build.cflags(target).join(" "))
            .arg("--llvm-components").arg(llvm_components.trim())
            .arg("--llvm-cxxflags").arg(llvm_cxxflags.trim());
+        // Tools aren't installed into the sysroot, where the makefiles
+        // would otherwise look for them.
+        cmd.env("APIDIFF_PATH", build.tool(compiler, "apidiff"));
     } else {
         cmd.arg("--cc").arg("")
            .arg("--cxx").arg("")
@@ -269,6 +269,9 @@ impl Build {
                     compile::tool(self, stage, target.target,
                                   "error_index_generator");
                 }
+                ToolApidiff { stage } => {
+                    compile::tool(self, stage, target.target, "apidiff");
+                }
                 ToolCargoTest { stage } => {
                     compile::tool(self, stage, target.target, "cargotest");
                 }
@@ -71,6 +71,7 @@ macro_rules! targets {
             (tool_linkchecker, ToolLinkchecker { stage: u32 }),
             (tool_rustbook, ToolRustbook { stage: u32 }),
             (tool_error_index, ToolErrorIndex { stage: u32 }),
+            (tool_apidiff, ToolApidiff { stage: u32 }),
             (tool_cargotest, ToolCargoTest { stage: u32 }),
             (tool_tidy, ToolTidy { stage: u32 }),
             (tool_compiletest, ToolCompiletest { stage: u32 }),
@@ -456,11 +457,15 @@ impl<'a> Step<'a> {
             Source::CheckCFailFull { compiler } |
             Source::CheckPrettyRPassFull { compiler } |
             Source::CheckPrettyRFailFull { compiler } |
-            Source::CheckPrettyRPassValgrind { compiler } |
-            Source::CheckRMake { compiler } => {
+            Source::CheckPrettyRPassValgrind { compiler } => {
                 vec![self.librustc(compiler),
                      self.tool_compiletest(compiler.stage)]
             }
+            Source::CheckRMake { compiler } => {
+                vec![self.librustc(compiler),
+                     self.tool_compiletest(compiler.stage),
+                     self.tool_apidiff(compiler.stage)]
+            }
             Source::CheckDocs { compiler } => {
                 vec![self.libstd(compiler)]
             }
@@ -482,6 +487,7 @@ impl<'a> Step<'a> {
                 vec![self.libstd(self.compiler(stage))]
             }
             Source::ToolErrorIndex { stage } |
+            Source::ToolApidiff { stage } |
             Source::ToolRustbook { stage } => {
                 vec![self.librustc(self.compiler(stage))]
             }
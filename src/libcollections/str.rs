@@ -1564,6 +1564,7 @@ impl str {
     /// assert_eq!("Hello\tworld", s.trim());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim(&self) -> &str {
         UnicodeStr::trim(self)
     }
@@ -1600,6 +1601,7 @@ impl str {
     /// assert!(Some('ע') == s.trim_left().chars().next());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim_left(&self) -> &str {
         UnicodeStr::trim_left(self)
     }
@@ -1636,6 +1638,7 @@ impl str {
     /// assert!(Some('ת') == s.trim_right().chars().rev().next());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim_right(&self) -> &str {
         UnicodeStr::trim_right(self)
     }
@@ -1666,6 +1669,7 @@ impl str {
     /// assert_eq!("1foo1barXX".trim_matches(|c| c == '1' || c == 'X'), "foo1bar");
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim_matches<'a, P: Pattern<'a>>(&'a self, pat: P) -> &'a str
         where P::Searcher: DoubleEndedSearcher<'a>
     {
@@ -1699,6 +1703,7 @@ impl str {
     /// assert_eq!("12foo1bar12".trim_left_matches(x), "foo1bar12");
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim_left_matches<'a, P: Pattern<'a>>(&'a self, pat: P) -> &'a str {
         core_str::StrExt::trim_left_matches(self, pat)
     }
@@ -1736,6 +1741,7 @@ impl str {
     /// assert_eq!("1fooX".trim_left_matches(|c| c == '1' || c == 'X'), "fooX");
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the trimmed string as a slice, without modifying the original"]
     pub fn trim_right_matches<'a, P: Pattern<'a>>(&'a self, pat: P) -> &'a str
         where P::Searcher: ReverseSearcher<'a>
     {
@@ -1817,6 +1823,7 @@ impl str {
     /// assert_eq!(s, s.replace("cookie monster", "little lamb"));
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[must_use = "this returns the result as a new `String`, without modifying the original"]
     pub fn replace<'a, P: Pattern<'a>>(&'a self, from: P, to: &str) -> String {
         let mut result = String::new();
         let mut last_end = 0;
@@ -1867,6 +1874,7 @@ impl str {
     /// assert_eq!(new_year, new_year.to_lowercase());
     /// ```
     #[stable(feature = "unicode_case_mapping", since = "1.2.0")]
+    #[must_use = "this returns the result as a new `String`, without modifying the original"]
     pub fn to_lowercase(&self) -> String {
         let mut s = String::with_capacity(self.len());
         for (i, c) in self[..].char_indices() {
@@ -1930,6 +1938,7 @@ impl str {
     /// assert_eq!(new_year, new_year.to_uppercase());
     /// ```
     #[stable(feature = "unicode_case_mapping", since = "1.2.0")]
+    #[must_use = "this returns the result as a new `String`, without modifying the original"]
     pub fn to_uppercase(&self) -> String {
         let mut s = String::with_capacity(self.len());
         s.extend(self.chars().flat_map(|c| c.to_uppercase()));
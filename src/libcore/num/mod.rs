@@ -415,6 +415,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_add(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_add(other);
             if b {None} else {Some(a)}
@@ -433,6 +434,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_sub(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_sub(other);
             if b {None} else {Some(a)}
@@ -451,6 +453,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_mul(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_mul(other);
             if b {None} else {Some(a)}
@@ -573,6 +576,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_add(self, other: Self) -> Self {
             match self.checked_add(other) {
                 Some(x) => x,
@@ -594,6 +598,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_sub(self, other: Self) -> Self {
             match self.checked_sub(other) {
                 Some(x) => x,
@@ -618,6 +623,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "wrapping", since = "1.7.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_mul(self, other: Self) -> Self {
             self.checked_mul(other).unwrap_or_else(|| {
                 if (self < 0 && other < 0) || (self > 0 && other > 0) {
@@ -641,6 +647,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_add(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_add(self, rhs)
@@ -660,6 +667,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_sub(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_sub(self, rhs)
@@ -679,6 +687,7 @@ macro_rules! int_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_mul(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_mul(self, rhs)
@@ -830,6 +839,7 @@ macro_rules! int_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $add_with_overflow(self as $ActualT,
@@ -856,6 +866,7 @@ macro_rules! int_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $sub_with_overflow(self as $ActualT,
@@ -880,6 +891,7 @@ macro_rules! int_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $mul_with_overflow(self as $ActualT,
@@ -1034,6 +1046,7 @@ macro_rules! int_impl {
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
         #[rustc_no_mir] // FIXME #29769 MIR overflow checking is TBD.
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn pow(self, mut exp: u32) -> Self {
             let mut base = self;
             let mut acc = Self::one();
@@ -1483,6 +1496,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_add(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_add(other);
             if b {None} else {Some(a)}
@@ -1501,6 +1515,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_sub(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_sub(other);
             if b {None} else {Some(a)}
@@ -1519,6 +1534,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn checked_mul(self, other: Self) -> Option<Self> {
             let (a, b) = self.overflowing_mul(other);
             if b {None} else {Some(a)}
@@ -1634,6 +1650,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_add(self, other: Self) -> Self {
             match self.checked_add(other) {
                 Some(x) => x,
@@ -1654,6 +1671,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_sub(self, other: Self) -> Self {
             match self.checked_sub(other) {
                 Some(x) => x,
@@ -1676,6 +1694,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "wrapping", since = "1.7.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn saturating_mul(self, other: Self) -> Self {
             self.checked_mul(other).unwrap_or(Self::max_value())
         }
@@ -1693,6 +1712,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_add(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_add(self, rhs)
@@ -1712,6 +1732,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_sub(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_sub(self, rhs)
@@ -1731,6 +1752,7 @@ macro_rules! uint_impl {
         /// ```
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn wrapping_mul(self, rhs: Self) -> Self {
             unsafe {
                 intrinsics::overflowing_mul(self, rhs)
@@ -1870,6 +1892,7 @@ macro_rules! uint_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $add_with_overflow(self as $ActualT,
@@ -1896,6 +1919,7 @@ macro_rules! uint_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $sub_with_overflow(self as $ActualT,
@@ -1920,6 +1944,7 @@ macro_rules! uint_impl {
         /// ```
         #[inline]
         #[stable(feature = "wrapping", since = "1.7.0")]
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
             unsafe {
                 let (a, b) = $mul_with_overflow(self as $ActualT,
@@ -2053,6 +2078,7 @@ macro_rules! uint_impl {
         #[stable(feature = "rust1", since = "1.0.0")]
         #[inline]
         #[rustc_no_mir] // FIXME #29769 MIR overflow checking is TBD.
+        #[must_use = "this returns the result of the operation, without modifying the original"]
         pub fn pow(self, mut exp: u32) -> Self {
             let mut base = self;
             let mut acc = Self::one();
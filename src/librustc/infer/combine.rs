@@ -39,7 +39,7 @@ use super::lub::Lub;
 use super::sub::Sub;
 use super::InferCtxt;
 use super::{MiscVariable, TypeTrace};
-use super::type_variable::{RelationDir, BiTo, EqTo, SubtypeOf, SupertypeOf};
+use super::type_variable::{Provenance, RelationDir, BiTo, EqTo, SubtypeOf, SupertypeOf};
 
 use ty::{IntType, UintType};
 use ty::{self, Ty, TyCtxt};
@@ -239,10 +239,18 @@ impl<'a, 'gcx, 'tcx> CombineFields<'a, 'gcx, 'tcx> {
                                         b_vid={:?}, generalized_ty={:?})",
                            a_ty, dir, b_vid,
                            generalized_ty);
+                    let provenance = if self.infcx.tcx.sess.opts.debugging_opts.explain_inference {
+                        Some(Provenance {
+                            origin: self.trace.origin,
+                            obligation: self.infcx.selecting_obligation(),
+                        })
+                    } else {
+                        None
+                    };
                     self.infcx.type_variables
                         .borrow_mut()
                         .instantiate_and_push(
-                            b_vid, generalized_ty, &mut stack);
+                            b_vid, generalized_ty, provenance, &mut stack);
                     generalized_ty
                 }
             };
@@ -83,6 +83,7 @@ use infer::{self, TypeOrigin};
 use middle::region;
 use ty::subst;
 use ty::{self, Ty, TyCtxt, TypeFoldable};
+use ty::fold::TypeVisitor;
 use ty::{Region, ReFree};
 use ty::error::TypeError;
 
@@ -501,9 +502,70 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             _ => ()
         }
 
+        match trace.values {
+            infer::Types(ref exp_found) => {
+                self.note_inference_provenance(&mut err, &exp_found.expected);
+                self.note_inference_provenance(&mut err, &exp_found.found);
+            }
+            infer::TraitRefs(ref exp_found) => {
+                self.note_inference_provenance(&mut err, &exp_found.expected);
+                self.note_inference_provenance(&mut err, &exp_found.found);
+            }
+            infer::PolyTraitRefs(ref exp_found) => {
+                self.note_inference_provenance(&mut err, &exp_found.expected);
+                self.note_inference_provenance(&mut err, &exp_found.found);
+            }
+        }
+
         err
     }
 
+    /// With `-Z explain-inference`, adds a note for each type variable in `value` pointing at
+    /// the constraint which first determined its type. The type variables appearing in those
+    /// types are followed in turn, so the notes form the chain of inference steps leading to
+    /// the types of `value`. Types inferred while proving a trait obligation also name that
+    /// obligation and its cause.
+    pub fn note_inference_provenance<T>(&self, err: &mut DiagnosticBuilder, value: &T)
+        where T: TypeFoldable<'tcx>
+    {
+        if !self.tcx.sess.opts.debugging_opts.explain_inference {
+            return;
+        }
+
+        let mut collector = TyVarCollector { vars: vec![] };
+        value.visit_with(&mut collector);
+
+        let mut seen = HashSet::new();
+        let mut i = 0;
+        while i < collector.vars.len() {
+            let vid = collector.vars[i];
+            i += 1;
+            let instantiation = self.type_variables.borrow_mut().instantiation(vid);
+            let (ty, provenance) = match instantiation {
+                Some(instantiation) => instantiation,
+                None => continue,
+            };
+            let span = provenance.origin.span();
+            if !seen.insert(span) {
+                continue;
+            }
+            ty.visit_with(&mut collector);
+            let ty = self.resolve_type_vars_if_possible(&ty);
+            match provenance.obligation {
+                Some(obligation) => {
+                    let obligation = self.resolve_type_vars_if_possible(&obligation);
+                    err.span_note(span,
+                                  &format!("the type `{}` was inferred here, while proving `{}`",
+                                           ty, obligation.predicate));
+                    self.note_obligation_cause(err, &obligation);
+                }
+                None => {
+                    err.span_note(span, &format!("the type `{}` was inferred here", ty));
+                }
+            }
+        }
+    }
+
     /// Adds a note if the types come from similarly named crates
     fn check_and_note_conflicting_crates(&self,
                                          err: &mut DiagnosticBuilder,
@@ -1788,6 +1850,22 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     }
 }
 
+/// Collects the type variables appearing in a type, in order.
+struct TyVarCollector {
+    vars: Vec<ty::TyVid>,
+}
+
+impl<'tcx> TypeVisitor<'tcx> for TyVarCollector {
+    fn visit_ty(&mut self, t: Ty<'tcx>) -> bool {
+        if let ty::TyInfer(ty::TyVar(vid)) = t.sty {
+            if !self.vars.contains(&vid) {
+                self.vars.push(vid);
+            }
+        }
+        t.super_visit_with(self)
+    }
+}
+
 pub trait Resolvable<'tcx> {
     fn resolve<'a, 'gcx>(&self, infcx: &InferCtxt<'a, 'gcx, 'tcx>) -> Self;
 }
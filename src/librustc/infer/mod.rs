@@ -38,6 +38,7 @@ use traits::{self, PredicateObligations, ProjectionMode};
 use rustc_data_structures::unify::{self, UnificationTable};
 use std::cell::{Cell, RefCell, Ref, RefMut};
 use std::fmt;
+use std::mem;
 use syntax::ast;
 use syntax::codemap;
 use syntax::codemap::{Span, DUMMY_SP};
@@ -168,6 +169,11 @@ pub struct InferCtxt<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     // any obligations set during the current snapshot. In that case, the
     // snapshot can't be rolled back.
     pub obligations_in_snapshot: Cell<bool>,
+
+    // The obligation trait selection is processing, to which the type
+    // variables instantiated meanwhile are traced back by
+    // `-Z explain-inference`. See `start_selecting()`.
+    selecting_obligation: RefCell<Option<traits::PredicateObligation<'tcx>>>,
 }
 
 /// A map returned by `skolemize_late_bound_regions()` indicating the skolemized
@@ -483,6 +489,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'gcx> {
             tainted_by_errors_flag: Cell::new(false),
             err_count_on_creation: self.sess.err_count(),
             obligations_in_snapshot: Cell::new(false),
+            selecting_obligation: RefCell::new(None),
         }
     }
 }
@@ -523,6 +530,7 @@ impl<'a, 'gcx, 'tcx> InferCtxtBuilder<'a, 'gcx, 'tcx> {
             tainted_by_errors_flag: Cell::new(false),
             err_count_on_creation: tcx.sess.err_count(),
             obligations_in_snapshot: Cell::new(false),
+            selecting_obligation: RefCell::new(None),
         }))
     }
 }
@@ -1271,6 +1279,30 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         self.tainted_by_errors_flag.set(true)
     }
 
+    /// Records `obligation` as the one trait selection or projection is
+    /// processing if `-Z explain-inference` is given, so the type variables
+    /// instantiated meanwhile can be traced back to it. Returns the
+    /// previously recorded obligation, to be passed to `finish_selecting()`.
+    pub fn start_selecting(&self, obligation: &traits::PredicateObligation<'tcx>)
+                           -> Option<traits::PredicateObligation<'tcx>> {
+        if !self.tcx.sess.opts.debugging_opts.explain_inference {
+            return None;
+        }
+        mem::replace(&mut *self.selecting_obligation.borrow_mut(), Some(obligation.clone()))
+    }
+
+    /// Restores the obligation recorded before the matching `start_selecting()`.
+    pub fn finish_selecting(&self, outer: Option<traits::PredicateObligation<'tcx>>) {
+        if self.tcx.sess.opts.debugging_opts.explain_inference {
+            *self.selecting_obligation.borrow_mut() = outer;
+        }
+    }
+
+    /// The obligation recorded by `start_selecting()`, if any.
+    pub fn selecting_obligation(&self) -> Option<traits::PredicateObligation<'tcx>> {
+        self.selecting_obligation.borrow().clone()
+    }
+
     pub fn node_type(&self, id: ast::NodeId) -> Ty<'tcx> {
         match self.tables.borrow().node_types.get(&id) {
             Some(&t) => t,
@@ -12,6 +12,8 @@ pub use self::RelationDir::*;
 use self::TypeVariableValue::*;
 use self::UndoEntry::*;
 use hir::def_id::{DefId};
+use infer::TypeOrigin;
+use traits::PredicateObligation;
 use ty::{self, Ty};
 use syntax::codemap::Span;
 
@@ -33,13 +35,28 @@ struct TypeVariableData<'tcx> {
 }
 
 enum TypeVariableValue<'tcx> {
-    Known(Ty<'tcx>),
+    Known {
+        value: Ty<'tcx>,
+        /// The constraint which determined `value`; only recorded with
+        /// `-Z explain-inference`.
+        provenance: Option<Provenance<'tcx>>,
+    },
     Bounded {
         relations: Vec<Relation>,
         default: Option<Default<'tcx>>
     }
 }
 
+/// Why a type variable was instantiated, as recorded by `-Z explain-inference`.
+#[derive(Clone)]
+pub struct Provenance<'tcx> {
+    /// The constraint that instantiated the variable.
+    pub origin: TypeOrigin,
+    /// The obligation trait selection or projection was processing when the
+    /// constraint was related, if any.
+    pub obligation: Option<PredicateObligation<'tcx>>,
+}
+
 // We will use this to store the required information to recapitulate what happened when
 // an error occurs.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -97,7 +114,7 @@ impl<'tcx> TypeVariableTable<'tcx> {
 
     pub fn default(&self, vid: ty::TyVid) -> Option<Default<'tcx>> {
         match &self.values.get(vid.index as usize).value {
-            &Known(_) => None,
+            &Known { .. } => None,
             &Bounded { ref default, .. } => default.clone()
         }
     }
@@ -142,25 +159,27 @@ impl<'tcx> TypeVariableTable<'tcx> {
 
     /// Instantiates `vid` with the type `ty` and then pushes an entry onto `stack` for each of the
     /// relations of `vid` to other variables. The relations will have the form `(ty, dir, vid1)`
-    /// where `vid1` is some other variable id.
+    /// where `vid1` is some other variable id. `origin` is the constraint which caused the
+    /// instantiation, if it is to be recorded.
     ///
     /// Precondition: `vid` must be a root in the unification table
     pub fn instantiate_and_push(
         &mut self,
         vid: ty::TyVid,
         ty: Ty<'tcx>,
+        provenance: Option<Provenance<'tcx>>,
         stack: &mut Vec<(Ty<'tcx>, RelationDir, ty::TyVid)>)
     {
         debug_assert!(self.root_var(vid) == vid);
         let old_value = {
             let value_ptr = &mut self.values.get_mut(vid.index as usize).value;
-            mem::replace(value_ptr, Known(ty))
+            mem::replace(value_ptr, Known { value: ty, provenance: provenance })
         };
 
         let (relations, default) = match old_value {
             Bounded { relations, default } => (relations, default),
-            Known(_) => bug!("Asked to instantiate variable that is \
-                              already instantiated")
+            Known { .. } => bug!("Asked to instantiate variable that is \
+                                  already instantiated")
         };
 
         for &(dir, vid) in &relations {
@@ -195,7 +214,19 @@ impl<'tcx> TypeVariableTable<'tcx> {
         debug_assert!(self.root_var(vid) == vid);
         match self.values.get(vid.index as usize).value {
             Bounded { .. } => None,
-            Known(t) => Some(t)
+            Known { value, .. } => Some(value)
+        }
+    }
+
+    /// The type `vid` was instantiated with, along with the constraint which caused the
+    /// instantiation, if it was recorded.
+    pub fn instantiation(&mut self, vid: ty::TyVid) -> Option<(Ty<'tcx>, Provenance<'tcx>)> {
+        let vid = self.root_var(vid);
+        match self.values.get(vid.index as usize).value {
+            Known { value, provenance: Some(ref provenance) } => {
+                Some((value, provenance.clone()))
+            }
+            _ => None
         }
     }
 
@@ -260,7 +291,7 @@ impl<'tcx> TypeVariableTable<'tcx> {
                         // created since the snapshot started or not.
                         let escaping_type = match self.values.get(vid.index as usize).value {
                             Bounded { .. } => bug!(),
-                            Known(ty) => ty,
+                            Known { value, .. } => value,
                         };
                         escaping_types.push(escaping_type);
                     }
@@ -318,7 +349,7 @@ impl<'tcx> sv::SnapshotVecDelegate for Delegate<'tcx> {
 
 fn relations<'a>(v: &'a mut TypeVariableData) -> &'a mut Vec<Relation> {
     match v.value {
-        Known(_) => bug!("var_sub_var: variable is known"),
+        Known { .. } => bug!("var_sub_var: variable is known"),
         Bounded { ref mut relations, .. } => relations
     }
 }
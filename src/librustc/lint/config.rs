@@ -0,0 +1,239 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lint configuration files.
+//!
+//! A lint configuration file sets the level of lints and lint groups for a
+//! whole crate, and can pass options to individual lints. It is either
+//! given with `-Z lint-config=PATH`, or is the first file named
+//! `rustc-lints.toml` found in the directory of the crate root or one of
+//! its ancestors. The file uses a small subset of TOML:
+//!
+//! ```toml
+//! # Levels, by lint or lint group name.
+//! [levels]
+//! unused_variables = "allow"
+//! bad-style = "deny"
+//!
+//! # Options of individual lints.
+//! [options.variant_size_differences]
+//! factor = 5
+//! ```
+//!
+//! Levels from the configuration file are applied before those given on the
+//! command line, which in turn can be overridden by attributes.
+
+use lint::Level;
+use util::nodemap::FnvHashMap;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The name of the file looked for next to the crate root.
+pub const LINT_CONFIG_FILE: &'static str = "rustc-lints.toml";
+
+/// The contents of a lint configuration file.
+pub struct LintConfig {
+    pub path: PathBuf,
+    /// Lint and lint group levels, with the line they are set on, in file
+    /// order.
+    pub levels: Vec<(String, Level, usize)>,
+    /// Options of each lint, keyed by lint name.
+    pub options: FnvHashMap<String, FnvHashMap<String, String>>,
+}
+
+/// An error found while loading a configuration file.
+pub struct LintConfigError {
+    /// The line the error is on, if it is a syntax error.
+    pub line: Option<usize>,
+    pub msg: String,
+}
+
+impl LintConfig {
+    /// Reads and parses the configuration file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, LintConfigError> {
+        let mut src = String::new();
+        if let Err(e) = File::open(path).and_then(|mut f| f.read_to_string(&mut src)) {
+            return Err(LintConfigError {
+                line: None,
+                msg: format!("couldn't read lint configuration file `{}`: {}",
+                             path.display(), e),
+            });
+        }
+        LintConfig::parse(path, &src)
+    }
+
+    pub fn parse(path: &Path, src: &str) -> Result<LintConfig, LintConfigError> {
+        let mut config = LintConfig {
+            path: path.to_path_buf(),
+            levels: Vec::new(),
+            options: FnvHashMap(),
+        };
+
+        // `None` in the `[levels]` table, `Some(lint)` in an options table.
+        let mut table: Option<Option<String>> = None;
+        for (i, line) in src.lines().enumerate() {
+            let lineno = i + 1;
+            let err = |msg: String| LintConfigError { line: Some(lineno), msg: msg };
+
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue
+            }
+
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err(err("expected `]` at the end of the table header".to_string()));
+                }
+                let name = line[1..line.len() - 1].trim();
+                table = if name == "levels" {
+                    Some(None)
+                } else if name.starts_with("options.") && name.len() > "options.".len() {
+                    Some(Some(normalize(&name["options.".len()..])))
+                } else {
+                    return Err(err(format!("unknown table `[{}]`, expected `[levels]` or \
+                                            `[options.<lint>]`", name)));
+                };
+                continue
+            }
+
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(err(format!("expected `key = value`, found `{}`", line))),
+            };
+            if key.is_empty() {
+                return Err(err("missing key before `=`".to_string()));
+            }
+            let value = match parse_value(value) {
+                Some(value) => value,
+                None => return Err(err(format!("invalid value `{}`", value))),
+            };
+
+            match table {
+                None => {
+                    return Err(err(format!("`{}` is not in a table, expected `[levels]` or \
+                                            `[options.<lint>]` first", key)));
+                }
+                Some(None) => {
+                    let level = match Level::from_str(&value) {
+                        Some(level) => level,
+                        None => {
+                            return Err(err(format!("unknown lint level `{}`, expected one of \
+                                                    `allow`, `warn`, `deny` or `forbid`",
+                                                   value)));
+                        }
+                    };
+                    config.levels.push((normalize(key), level, lineno));
+                }
+                Some(Some(ref lint)) => {
+                    config.options.entry(lint.clone())
+                                  .or_insert_with(FnvHashMap)
+                                  .insert(normalize(key), value);
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Looks for a configuration file in `dir` and its ancestors.
+pub fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    // The ancestors of a relative path stop short of the working directory
+    // (the crate root `foo.rs` is in the empty path, which has none).
+    let dir = match env::current_dir() {
+        Ok(cwd) => cwd.join(dir),
+        Err(_) => dir.to_path_buf(),
+    };
+    let mut dir = Some(dir.as_path());
+    while let Some(d) = dir {
+        let candidate = d.join(LINT_CONFIG_FILE);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// Lint names and option keys may be written with dashes, as on the
+/// command line.
+fn normalize(name: &str) -> String {
+    name.trim().replace("-", "_")
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Values are either quoted strings (without escapes) or bare words such as
+/// numbers and booleans.
+fn parse_value(value: &str) -> Option<String> {
+    if value.starts_with('"') {
+        if value.len() >= 2 && value.ends_with('"') && !value[1..value.len() - 1].contains('"') {
+            Some(value[1..value.len() - 1].to_string())
+        } else {
+            None
+        }
+    } else if !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() ||
+                                                         c == '_' || c == '-' || c == '.') {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LintConfig;
+    use lint::Level;
+
+    use std::path::Path;
+
+    fn parse(src: &str) -> Result<LintConfig, (Option<usize>, String)> {
+        LintConfig::parse(Path::new("rustc-lints.toml"), src).map_err(|e| (e.line, e.msg))
+    }
+
+    #[test]
+    fn levels_and_options() {
+        let config = parse("# lint policy\n\
+                            [levels]\n\
+                            unused-variables = \"allow\"  # noisy\n\
+                            bad_style = deny\n\
+                            \n\
+                            [options.missing_docs]\n\
+                            min-items = 3\n\
+                            message = \"see # docs\"\n").ok().unwrap();
+        assert_eq!(config.levels, vec![("unused_variables".to_string(), Level::Allow, 3),
+                                       ("bad_style".to_string(), Level::Deny, 4)]);
+        let options = &config.options["missing_docs"];
+        assert_eq!(options["min_items"], "3");
+        assert_eq!(options["message"], "see # docs");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse("unused_variables = \"allow\"").err().unwrap().0, Some(1));
+        assert_eq!(parse("[levels]\nunused_variables = \"loud\"").err().unwrap().0, Some(2));
+        assert_eq!(parse("[lints]").err().unwrap().0, Some(1));
+        assert_eq!(parse("[levels]\nunused_variables").err().unwrap().0, Some(2));
+        assert_eq!(parse("[levels]\nfoo = \"a\"b\"").err().unwrap().0, Some(2));
+    }
+}
@@ -31,14 +31,16 @@ use ty::TyCtxt;
 use session::{config, early_error, Session};
 use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass};
 use lint::{EarlyLintPassObject, LateLintPass, LateLintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
+use lint::config::{find_config_file, LintConfig, LINT_CONFIG_FILE};
 use util::nodemap::FnvHashMap;
 
 use std::cell::RefCell;
 use std::cmp;
 use std::default::Default as StdDefault;
 use std::mem;
+use std::path::PathBuf;
 use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap::Span;
 use syntax::errors::DiagnosticBuilder;
@@ -81,6 +83,12 @@ pub struct LintStore {
 
     /// Maximum level a lint can be
     lint_cap: Option<Level>,
+
+    /// The lint configuration file in use, if any.
+    config_file: Option<PathBuf>,
+
+    /// Options given to lints by the configuration file, keyed by lint name.
+    lint_options: FnvHashMap<String, FnvHashMap<String, String>>,
 }
 
 /// Extra information for a future incompatibility lint. See the call
@@ -138,6 +146,8 @@ impl LintStore {
             future_incompatible: FnvHashMap(),
             lint_groups: FnvHashMap(),
             lint_cap: None,
+            config_file: None,
+            lint_options: FnvHashMap(),
         }
     }
 
@@ -256,7 +266,91 @@ impl LintStore {
         }
     }
 
+    /// The value of the option `key` of `lint`, as given by the lint
+    /// configuration file.
+    pub fn lint_option(&self, lint: &'static Lint, key: &str) -> Option<&str> {
+        self.lint_options.get(&lint.name_lower())
+                         .and_then(|options| options.get(key))
+                         .map(|value| &value[..])
+    }
+
+    /// Loads the lint configuration file, either the one given with
+    /// `-Z lint-config` or the first one found next to the crate root or in
+    /// one of its parent directories.
+    fn process_config_file(&mut self, sess: &Session) {
+        let path = match sess.opts.debugging_opts.lint_config {
+            Some(ref path) => Some(PathBuf::from(path)),
+            None => {
+                sess.local_crate_source_file.as_ref()
+                    .and_then(|file| file.parent())
+                    .and_then(find_config_file)
+            }
+        };
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let config = match LintConfig::load(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                match e.line {
+                    Some(line) => sess.err(&format!("{}:{}: {}", path.display(), line, e.msg)),
+                    None => sess.err(&e.msg),
+                }
+                return
+            }
+        };
+
+        for &(ref lint_name, level, line) in &config.levels {
+            let note = format!("set in the lint configuration file at {}:{}",
+                               path.display(), line);
+            check_lint_name_config(sess, self, lint_name, &note);
+
+            let ids = match self.find_lint(lint_name, sess, None) {
+                Ok(lint_id) => vec![lint_id],
+                Err(FindLintError::Removed) => continue,
+                Err(FindLintError::NotFound) => {
+                    match self.lint_groups.get(&lint_name[..]) {
+                        Some(&(ref v, _)) => v.clone(),
+                        // Reported by check_lint_name_config.
+                        None => continue,
+                    }
+                }
+            };
+            for lint_id in ids {
+                self.set_level(lint_id, (level, ConfigFile));
+            }
+        }
+
+        for (lint_name, options) in config.options {
+            match self.by_name.get(&lint_name) {
+                Some(&Id(_)) => {}
+                Some(&Renamed(ref new_name, _)) => {
+                    sess.struct_err(&format!("options given to lint {}, which has been \
+                                              renamed to {}", lint_name, new_name))
+                        .note(&format!("in the lint configuration file at {}", path.display()))
+                        .emit();
+                    continue
+                }
+                _ => {
+                    sess.struct_err(&format!("options given to unknown lint: `{}`", lint_name))
+                        .note(&format!("in the lint configuration file at {}", path.display()))
+                        .emit();
+                    continue
+                }
+            }
+            self.lint_options.insert(lint_name, options);
+        }
+
+        self.config_file = Some(path);
+    }
+
     pub fn process_command_line(&mut self, sess: &Session) {
+        // The configuration file comes first, so that the command line can
+        // override it.
+        self.process_config_file(sess);
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             check_lint_name_cmdline(sess, self,
                                     &lint_name[..], level);
@@ -432,6 +526,11 @@ pub fn raw_struct_lint<'a>(sess: &'a Session,
                         Allow => bug!()
                     }, name.replace("_", "-"))
         },
+        ConfigFile => {
+            let file = lints.config_file.as_ref().map(|p| p.display().to_string());
+            format!("{} [{}({}) in {}]", msg, level.as_str(), name,
+                    file.unwrap_or_else(|| LINT_CONFIG_FILE.to_string()))
+        },
         Node(src) => {
             def = Some(src);
             msg.to_string()
@@ -448,6 +547,7 @@ pub fn raw_struct_lint<'a>(sess: &'a Session,
         (Deny, None)     => sess.struct_err(&msg[..]),
         _ => bug!("impossible level in raw_emit_lint"),
     };
+    err.lint(&name);
 
     // Check for future incompatibility lints and issue a stronger warning.
     if let Some(future_incompatible) = lints.future_incompatible(LintId::of(lint)) {
@@ -519,6 +619,12 @@ pub trait LintContext: Sized {
         raw_struct_lint(&self.sess(), self.lints(), lint, (level, src), span, msg)
     }
 
+    /// The value of an option given to `lint` in the lint configuration
+    /// file.
+    fn lint_option(&self, lint: &'static Lint, key: &str) -> Option<&str> {
+        self.lints().lint_option(lint, key)
+    }
+
     /// Emit a lint at the appropriate level, for a particular span.
     fn span_lint(&self, lint: &'static Lint, span: Span, msg: &str) {
         self.lookup_and_emit(lint, Some(span), msg);
@@ -1206,6 +1312,21 @@ fn check_lint_name_cmdline(sess: &Session, lint_cx: &LintStore,
     }
 }
 
+// Checks the validity of lint names from the lint configuration file
+fn check_lint_name_config(sess: &Session, lint_cx: &LintStore, lint_name: &str, note: &str) {
+    let db = match check_lint_name(lint_cx, lint_name) {
+        CheckLintNameResult::Ok => None,
+        CheckLintNameResult::Warning(ref msg) => Some(sess.struct_warn(msg)),
+        CheckLintNameResult::NoLint => {
+            Some(sess.struct_err(&format!("unknown lint: `{}`", lint_name)))
+        }
+    };
+
+    if let Some(mut db) = db {
+        db.note(note);
+        db.emit();
+    }
+}
 
 /// Perform lint checking on a crate.
 ///
@@ -310,10 +310,14 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine,
+
+    /// Lint level was set by the lint configuration file.
+    ConfigFile,
 }
 
 pub type LevelSource = (Level, LintSource);
 
 pub mod builtin;
 
+mod config;
 mod context;
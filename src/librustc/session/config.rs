@@ -74,6 +74,8 @@ pub enum OutputType {
 pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json,
+    Short,
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -709,6 +711,22 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "dump MIR state at various points in translation"),
     orbit: bool = (false, parse_bool,
           "get MIR where it belongs - everywhere; most importantly, in orbit"),
+    error_limit: Option<usize> = (None, parse_opt_uint,
+          "abort compilation after this many errors"),
+    no_dedup_diagnostics: bool = (false, parse_bool,
+          "emit identical diagnostics every time instead of only once"),
+    apply_suggestions: Option<String> = (None, parse_opt_string,
+          "apply the code suggestions known to be right to the source files (inplace|diff)"),
+    explain_inference: bool = (false, parse_bool,
+          "explain where inferred types come from in type errors"),
+    lint_config: Option<String> = (None, parse_opt_string,
+          "read lint levels and options from this file instead of looking for a \
+           rustc-lints.toml file next to the crate root"),
+    dump_obligations: Option<String> = (None, parse_opt_string,
+          "dump the trait obligations of items whose path contains this string (or `all`), \
+           with the selection decisions made for them"),
+    dump_obligations_format: Option<String> = (None, parse_opt_string,
+          "the format of -Z dump-obligations output (dot|json)"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -983,7 +1001,8 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         opt::multi_ubnr("Z", "", "Set internal debugging options", "FLAG"),
         opt::opt_ubnr("", "error-format",
                       "How errors and other messages are produced",
-                      "human|json"),
+                      "human|json|short|sarif"),
+        opt::opt_ubnr("", "cap-errors", "Abort compilation after this many errors", "N"),
         opt::opt_s("", "color", "Configure coloring of output:
                                  auto   = colorize, if output goes to a tty (default);
                                  always = always colorize output;
@@ -1057,13 +1076,15 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
             Some("human")   => ErrorOutputType::HumanReadable(color),
             Some("json") => ErrorOutputType::Json,
+            Some("short") => ErrorOutputType::Short,
+            Some("sarif") => ErrorOutputType::Sarif,
 
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => {
                 early_error(ErrorOutputType::HumanReadable(color),
-                            &format!("argument for --error-format must be human or json (instead \
-                                      was `{}`)",
+                            &format!("argument for --error-format must be human, json, short \
+                                      or sarif (instead was `{}`)",
                                      arg))
             }
         }
@@ -1094,7 +1115,18 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         })
     });
 
-    let debugging_opts = build_debugging_options(matches, error_format);
+    let mut debugging_opts = build_debugging_options(matches, error_format);
+
+    // `--cap-errors` is the same as `-Z error-limit`.
+    if let Some(cap) = matches.opt_str("cap-errors") {
+        match cap.parse() {
+            Ok(cap) => debugging_opts.error_limit = Some(cap),
+            Err(_) => {
+                early_error(error_format, &format!("argument for --cap-errors must be a \
+                                                    number (instead was `{}`)", cap))
+            }
+        }
+    }
 
     let parse_only = debugging_opts.parse_only;
     let no_trans = debugging_opts.no_trans;
@@ -1103,6 +1135,14 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let mir_opt_level = debugging_opts.mir_opt_level.unwrap_or(1);
     let no_analysis = debugging_opts.no_analysis;
 
+    match debugging_opts.dump_obligations_format.as_ref().map(|s| &s[..]) {
+        None | Some("dot") | Some("json") => {}
+        Some(format) => {
+            early_error(error_format, &format!("unknown obligation dump format `{}`, \
+                                                expected `dot` or `json`", format))
+        }
+    }
+
     let mut output_types = HashMap::new();
     if !debugging_opts.parse_only {
         for list in matches.opt_strs("emit") {
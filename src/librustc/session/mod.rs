@@ -23,8 +23,10 @@ use mir::transform as mir_pass;
 use syntax::ast::{NodeId, NodeIdAssigner, Name};
 use syntax::codemap::{Span, MultiSpan};
 use syntax::errors::{self, DiagnosticBuilder};
-use syntax::errors::emitter::{Emitter, BasicEmitter, EmitterWriter};
+use syntax::errors::emitter::{Emitter, BasicEmitter, ColorConfig, EmitterWriter};
 use syntax::errors::json::JsonEmitter;
+use syntax::errors::sarif::SarifEmitter;
+use syntax::errors::short::ShortEmitter;
 use syntax::diagnostics;
 use syntax::feature_gate;
 use syntax::parse;
@@ -463,12 +465,20 @@ pub fn build_session_with_codemap(sopts: config::Options,
         config::ErrorOutputType::Json => {
             Box::new(JsonEmitter::stderr(Some(registry), codemap.clone()))
         }
+        config::ErrorOutputType::Short => {
+            Box::new(ShortEmitter::stderr(codemap.clone()))
+        }
+        config::ErrorOutputType::Sarif => {
+            Box::new(SarifEmitter::stderr(Some(registry), codemap.clone()))
+        }
     };
 
     let diagnostic_handler =
         errors::Handler::with_emitter(can_print_warnings,
                                       treat_err_as_bug,
                                       emitter);
+    diagnostic_handler.set_error_limit(sopts.debugging_opts.error_limit);
+    diagnostic_handler.set_deduplicate(!sopts.debugging_opts.no_dedup_diagnostics);
 
     build_session_(sopts,
                    dep_graph,
@@ -598,6 +608,8 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
             Box::new(BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Short => Box::new(BasicEmitter::stderr(ColorConfig::Never)),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     emitter.emit(&MultiSpan::new(), msg, None, errors::Level::Fatal);
     panic!(errors::FatalError);
@@ -609,6 +621,8 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
             Box::new(BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Short => Box::new(BasicEmitter::stderr(ColorConfig::Never)),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     emitter.emit(&MultiSpan::new(), msg, None, errors::Level::Warning);
 }
@@ -599,6 +599,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                 }
             }
         };
+        self.note_inference_provenance(&mut err, &obligation.predicate);
         self.note_obligation_cause(&mut err, obligation);
         err.emit();
     }
@@ -836,9 +837,9 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                   ty);
     }
 
-    fn note_obligation_cause<T>(&self,
-                                err: &mut DiagnosticBuilder,
-                                obligation: &Obligation<'tcx, T>)
+    pub fn note_obligation_cause<T>(&self,
+                                    err: &mut DiagnosticBuilder,
+                                    obligation: &Obligation<'tcx, T>)
         where T: fmt::Display
     {
         self.note_obligation_cause_code(err,
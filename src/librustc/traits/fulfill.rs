@@ -28,6 +28,7 @@ use super::ObligationCause;
 use super::PredicateObligation;
 use super::project;
 use super::select::SelectionContext;
+use super::trace::{self, ObligationTrace, SelectionEvent, SelectionEventKind, TraceResult};
 use super::Unimplemented;
 
 impl<'tcx> ForestObligation for PendingPredicateObligation<'tcx> {
@@ -84,6 +85,10 @@ pub struct FulfillmentContext<'tcx> {
     // obligations (otherwise, it's easy to fail to walk to a
     // particular node-id).
     region_obligations: NodeMap<Vec<RegionObligation<'tcx>>>,
+
+    // The obligations registered for the item selected by
+    // `-Z dump-obligations`, if any.
+    trace: Option<ObligationTrace>,
 }
 
 #[derive(Clone)]
@@ -97,6 +102,8 @@ pub struct RegionObligation<'tcx> {
 pub struct PendingPredicateObligation<'tcx> {
     pub obligation: PredicateObligation<'tcx>,
     pub stalled_on: Vec<Ty<'tcx>>,
+    /// The index of the obligation in the fulfillment context's trace.
+    pub trace_id: Option<usize>,
 }
 
 impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
@@ -106,6 +113,7 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
             predicates: ObligationForest::new(),
             rfc1592_obligations: Vec::new(),
             region_obligations: NodeMap(),
+            trace: None,
         }
     }
 
@@ -173,17 +181,51 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
 
         infcx.obligations_in_snapshot.set(true);
 
+        let trace_id = self.trace_obligation(infcx.tcx, &obligation);
+
         if infcx.tcx.fulfilled_predicates.borrow().check_duplicate(&obligation.predicate)
         {
+            if let Some(index) = trace_id {
+                let event = SelectionEvent {
+                    depth: obligation.recursion_depth,
+                    kind: SelectionEventKind::CacheHit,
+                    detail: format!("{:?} was already proven elsewhere", obligation.predicate),
+                };
+                let trace = self.trace.as_mut().unwrap();
+                trace.record(index, &obligation, TraceResult::Proven, vec![event]);
+            }
             return
         }
 
         self.predicates.register_obligation(PendingPredicateObligation {
             obligation: obligation,
-            stalled_on: vec![]
+            stalled_on: vec![],
+            trace_id: trace_id,
         });
     }
 
+    /// Adds `obligation` to the trace if the item it comes from is
+    /// selected by `-Z dump-obligations`.
+    fn trace_obligation(&mut self,
+                        tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                        obligation: &PredicateObligation<'tcx>)
+                        -> Option<usize>
+    {
+        let item_id = match trace::traced_item(tcx, obligation.cause.body_id) {
+            Some(item_id) => item_id,
+            None => return None,
+        };
+        if self.trace.is_none() {
+            self.trace = Some(ObligationTrace::new(tcx, item_id));
+        }
+        let trace = self.trace.as_mut().unwrap();
+        if trace.item_id() == item_id {
+            Some(trace.push(tcx, obligation, None))
+        } else {
+            None
+        }
+    }
+
     pub fn register_rfc1592_obligation(&mut self,
                                        _infcx: &InferCtxt<'a, 'gcx, 'tcx>,
                                        obligation: PredicateObligation<'tcx>)
@@ -227,6 +269,10 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
                            .into_iter()
                            .map(|e| to_fulfillment_error(e))
                            .collect();
+        // Nothing is left to select, so the trace is complete.
+        if let Some(ref trace) = self.trace {
+            trace.dump(infcx.tcx);
+        }
         if errors.is_empty() {
             Ok(())
         } else {
@@ -261,7 +307,8 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
             let outcome = self.predicates.process_obligations(&mut FulfillProcessor {
                     selcx: selcx,
                     region_obligations: &mut self.region_obligations,
-                    rfc1592_obligations: &mut self.rfc1592_obligations
+                    rfc1592_obligations: &mut self.rfc1592_obligations,
+                    trace: &mut self.trace,
             });
             debug!("select: outcome={:?}", outcome);
 
@@ -285,6 +332,13 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
         debug!("select({} predicates remaining, {} errors) done",
                self.predicates.len(), errors.len());
 
+        // Compilation may stop at the errors, before the context is done.
+        if !errors.is_empty() {
+            if let Some(ref trace) = self.trace {
+                trace.dump(selcx.tcx());
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -296,7 +350,8 @@ impl<'a, 'gcx, 'tcx> FulfillmentContext<'tcx> {
 struct FulfillProcessor<'a, 'b: 'a, 'gcx: 'tcx, 'tcx: 'b> {
     selcx: &'a mut SelectionContext<'b, 'gcx, 'tcx>,
     region_obligations: &'a mut NodeMap<Vec<RegionObligation<'tcx>>>,
-    rfc1592_obligations: &'a mut Vec<PredicateObligation<'tcx>>
+    rfc1592_obligations: &'a mut Vec<PredicateObligation<'tcx>>,
+    trace: &'a mut Option<ObligationTrace>,
 }
 
 impl<'a, 'b, 'gcx, 'tcx> ObligationProcessor for FulfillProcessor<'a, 'b, 'gcx, 'tcx> {
@@ -307,14 +362,43 @@ impl<'a, 'b, 'gcx, 'tcx> ObligationProcessor for FulfillProcessor<'a, 'b, 'gcx,
                           obligation: &mut Self::Obligation)
                           -> Result<Option<Vec<Self::Obligation>>, Self::Error>
     {
-        process_predicate(self.selcx,
-                          obligation,
-                          self.region_obligations,
-                          self.rfc1592_obligations)
-            .map(|os| os.map(|os| os.into_iter().map(|o| PendingPredicateObligation {
+        let trace_id = obligation.trace_id;
+        if trace_id.is_some() {
+            self.selcx.start_trace();
+        }
+
+        let infcx = self.selcx.infcx();
+        let outer = infcx.start_selecting(&obligation.obligation);
+        let result = process_predicate(self.selcx,
+                                       obligation,
+                                       self.region_obligations,
+                                       self.rfc1592_obligations);
+        infcx.finish_selecting(outer);
+
+        if let Some(index) = trace_id {
+            let traced_result = match result {
+                Ok(None) => TraceResult::Ambiguous,
+                Ok(Some(_)) => TraceResult::Proven,
+                Err(ref e) => TraceResult::Error(format!("{:?}", e)),
+            };
+            let events = self.selcx.take_trace();
+            let trace = self.trace.as_mut().unwrap();
+            trace.record(index, &obligation.obligation, traced_result, events);
+        }
+
+        let tcx = self.selcx.tcx();
+        let trace = &mut *self.trace;
+        result.map(|os| os.map(|os| os.into_iter().map(|o| {
+            // Nested obligations are traced along with their parent.
+            let child_id = trace_id.map(|index| {
+                trace.as_mut().unwrap().push(tcx, &o, Some(index))
+            });
+            PendingPredicateObligation {
                 obligation: o,
-                stalled_on: vec![]
-            }).collect()))
+                stalled_on: vec![],
+                trace_id: child_id,
+            }
+        }).collect()))
     }
 
     fn process_backedge<'c, I>(&mut self, cycle: I,
@@ -381,6 +465,8 @@ fn process_predicate<'a, 'gcx, 'tcx>(
     match obligation.predicate {
         ty::Predicate::Trait(ref data) => {
             if selcx.tcx().fulfilled_predicates.borrow().check_duplicate_trait(data) {
+                selcx.record_event(obligation.recursion_depth, SelectionEventKind::CacheHit,
+                                   || format!("{:?} was already proven elsewhere", data));
                 return Ok(Some(vec![]));
             }
 
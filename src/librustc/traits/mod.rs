@@ -54,6 +54,7 @@ mod object_safety;
 mod select;
 mod specialize;
 mod structural_impls;
+mod trace;
 mod util;
 
 /// An `Obligation` represents some trait reference (e.g. `int:Eq`) for
@@ -27,6 +27,7 @@ use super::ProjectionMode;
 use super::TraitNotObjectSafe;
 use super::Selection;
 use super::SelectionResult;
+use super::trace::{SelectionEvent, SelectionEventKind};
 use super::{VtableBuiltin, VtableImpl, VtableParam, VtableClosure,
             VtableFnPointer, VtableObject, VtableDefaultImpl};
 use super::{VtableImplData, VtableObjectData, VtableBuiltinData,
@@ -87,6 +88,10 @@ pub struct SelectionContext<'cx, 'gcx: 'cx+'tcx, 'tcx: 'cx> {
     intercrate: bool,
 
     inferred_obligations: SnapshotVec<InferredObligationsSnapshotVecDelegate<'tcx>>,
+
+    /// The decisions made since `start_trace` was called, for
+    /// `-Z dump-obligations`.
+    trace: Option<Vec<SelectionEvent>>,
 }
 
 // A stack that walks back up the stack frame.
@@ -314,6 +319,7 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
             freshener: infcx.freshener(),
             intercrate: false,
             inferred_obligations: SnapshotVec::new(),
+            trace: None,
         }
     }
 
@@ -323,6 +329,7 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
             freshener: infcx.freshener(),
             intercrate: true,
             inferred_obligations: SnapshotVec::new(),
+            trace: None,
         }
     }
 
@@ -346,6 +353,30 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
         self.infcx.projection_mode()
     }
 
+    /// Starts recording the decisions made by selection.
+    pub fn start_trace(&mut self) {
+        self.trace = Some(vec![]);
+    }
+
+    /// Stops recording, returning the decisions made since `start_trace`.
+    pub fn take_trace(&mut self) -> Vec<SelectionEvent> {
+        self.trace.take().unwrap_or(vec![])
+    }
+
+    /// Records a decision if a trace is being recorded; `detail` is only
+    /// called in that case.
+    pub fn record_event<F>(&mut self, depth: usize, kind: SelectionEventKind, detail: F)
+        where F: FnOnce() -> String
+    {
+        if let Some(ref mut trace) = self.trace {
+            trace.push(SelectionEvent {
+                depth: depth,
+                kind: kind,
+                detail: detail(),
+            });
+        }
+    }
+
     /// Wraps the inference context's in_snapshot s.t. snapshot handling is only from the selection
     /// context's self.
     fn in_snapshot<R, F>(&mut self, f: F) -> R
@@ -601,6 +632,8 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
             debug!("CACHE HIT: EVAL({:?})={:?}",
                    fresh_trait_ref,
                    result);
+            self.record_event(obligation.recursion_depth, SelectionEventKind::CacheHit,
+                              || format!("evaluation of {:?}: {:?}", fresh_trait_ref, result));
             return result;
         }
 
@@ -718,6 +751,8 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
         });
         debug!("evaluate_candidate: depth={} result={:?}",
                stack.obligation.recursion_depth, result);
+        self.record_event(stack.obligation.recursion_depth, SelectionEventKind::Evaluated,
+                          || format!("{:?}: {:?}", candidate, result));
         result
     }
 
@@ -794,6 +829,9 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
                 debug!("CACHE HIT: SELECT({:?})={:?}",
                        cache_fresh_trait_pred,
                        c);
+                self.record_event(stack.obligation.recursion_depth, SelectionEventKind::CacheHit,
+                                  || format!("selection for {:?}: {:?}",
+                                             cache_fresh_trait_pred, c));
                 return c;
             }
             None => { }
@@ -802,6 +840,21 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
         // If no match, compute result and insert into cache.
         let candidate = self.candidate_from_obligation_no_cache(stack);
 
+        let depth = stack.obligation.recursion_depth;
+        match candidate {
+            Ok(Some(ref c)) => {
+                self.record_event(depth, SelectionEventKind::Selected, || format!("{:?}", c));
+            }
+            Ok(None) => {
+                self.record_event(depth, SelectionEventKind::Ambiguous,
+                                  || format!("{:?}", stack.obligation.predicate));
+            }
+            Err(ref e) => {
+                self.record_event(depth, SelectionEventKind::Failed,
+                                  || format!("{:?}: {:?}", stack.obligation.predicate, e));
+            }
+        }
+
         if self.should_update_candidate_cache(&cache_fresh_trait_pred, &candidate) {
             debug!("CACHE MISS: SELECT({:?})={:?}",
                    cache_fresh_trait_pred, candidate);
@@ -844,6 +897,11 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
 
         let candidate_set = self.assemble_candidates(stack)?;
 
+        self.record_event(stack.obligation.recursion_depth, SelectionEventKind::Candidates,
+                          || format!("{:?}: {:?}{}", stack.obligation.predicate,
+                                     candidate_set.vec,
+                                     if candidate_set.ambiguous { " (ambiguous)" } else { "" }));
+
         if candidate_set.ambiguous {
             debug!("candidate set contains ambig");
             return Ok(None);
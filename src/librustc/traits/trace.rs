@@ -0,0 +1,252 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Traces of the obligations processed by a fulfillment context, written
+//! out by `-Z dump-obligations=<filter>`.
+//!
+//! The obligation forest forgets obligations as soon as they are proven,
+//! so when an item is selected by the filter, its fulfillment context keeps
+//! a separate record of every obligation it registers, the obligation it
+//! was derived from, the decisions made by selection while processing it
+//! (including cache hits) and the final result. The filter is a
+//! `&`-separated list of substrings of item paths, or `all`, as for
+//! `-Z dump-mir`.
+//!
+//! Each trace is written to
+//!
+//! ```text
+//! rustc.node<node_id>.obligations.<disambiguator>.<dot|json>
+//! ```
+//!
+//! in the format given by `-Z dump-obligations-format` (graphviz by
+//! default) once the context has selected all of its obligations, as well
+//! as whenever selection fails, since compilation may stop at the errors.
+
+use graphviz as dot;
+use graphviz::IntoCow;
+use hir::map as ast_map;
+use serialize::json;
+use ty::TyCtxt;
+
+use std::fs::File;
+use std::io::Write;
+use std::iter;
+use syntax::ast;
+
+use super::PredicateObligation;
+
+/// The obligations registered for one item in one fulfillment context.
+#[derive(RustcEncodable)]
+pub struct ObligationTrace {
+    /// The path of the item.
+    item: String,
+    item_id: ast::NodeId,
+    /// Distinguishes the traces of several fulfillment contexts.
+    disambiguator: usize,
+    obligations: Vec<TracedObligation>,
+}
+
+#[derive(RustcEncodable)]
+struct TracedObligation {
+    predicate: String,
+    span: String,
+    /// The obligation this one is nested in.
+    parent: Option<usize>,
+    result: TraceResult,
+    /// The selection events of every attempt to process the obligation.
+    events: Vec<SelectionEvent>,
+}
+
+#[derive(Clone, Debug, RustcEncodable)]
+pub enum TraceResult {
+    /// The obligation was never processed; either because it is a
+    /// duplicate of another obligation, or because it is still pending.
+    Pending,
+    /// The last attempt to process the obligation was inconclusive.
+    Ambiguous,
+    /// The obligation holds, provided its nested obligations do.
+    Proven,
+    Error(String),
+}
+
+/// A decision made by selection.
+#[derive(RustcEncodable)]
+pub struct SelectionEvent {
+    /// The recursion depth of the obligation the event is about. Nested
+    /// obligations evaluated while winnowing candidates are deeper.
+    pub depth: usize,
+    pub kind: SelectionEventKind,
+    pub detail: String,
+}
+
+#[derive(Copy, Clone, Debug, RustcEncodable)]
+pub enum SelectionEventKind {
+    /// A result was taken from a cache, or the predicate was already
+    /// proven elsewhere.
+    CacheHit,
+    /// The candidates assembled for an obligation.
+    Candidates,
+    /// The result of evaluating a candidate.
+    Evaluated,
+    /// The candidate selected for an obligation.
+    Selected,
+    /// Selection could not decide between candidates, or lacked
+    /// information.
+    Ambiguous,
+    /// Selection failed, usually because no candidate applies.
+    Failed,
+}
+
+/// Returns the item enclosing `body_id` if its obligations are to be traced.
+pub fn traced_item(tcx: TyCtxt, body_id: ast::NodeId) -> Option<ast::NodeId> {
+    let filters = match tcx.sess.opts.debugging_opts.dump_obligations {
+        None => return None,
+        Some(ref filters) => filters,
+    };
+    let item_id = match tcx.map.find(body_id) {
+        Some(ast_map::NodeItem(_)) |
+        Some(ast_map::NodeTraitItem(_)) |
+        Some(ast_map::NodeImplItem(_)) |
+        Some(ast_map::NodeForeignItem(_)) => body_id,
+        Some(_) => tcx.map.get_parent(body_id),
+        None => return None,
+    };
+    let item_path = tcx.item_path_str(tcx.map.local_def_id(item_id));
+    if filters.split("&").any(|filter| filter == "all" || item_path.contains(filter)) {
+        Some(item_id)
+    } else {
+        None
+    }
+}
+
+impl ObligationTrace {
+    pub fn new(tcx: TyCtxt, item_id: ast::NodeId) -> ObligationTrace {
+        let disambiguator = tcx.obligation_traces.get();
+        tcx.obligation_traces.set(disambiguator + 1);
+        ObligationTrace {
+            item: tcx.item_path_str(tcx.map.local_def_id(item_id)),
+            item_id: item_id,
+            disambiguator: disambiguator,
+            obligations: vec![],
+        }
+    }
+
+    pub fn item_id(&self) -> ast::NodeId {
+        self.item_id
+    }
+
+    /// Adds an obligation to the trace, returning its index.
+    pub fn push<'tcx>(&mut self,
+                      tcx: TyCtxt,
+                      obligation: &PredicateObligation<'tcx>,
+                      parent: Option<usize>)
+                      -> usize {
+        self.obligations.push(TracedObligation {
+            predicate: obligation.predicate.to_string(),
+            span: tcx.sess.codemap().span_to_string(obligation.cause.span),
+            parent: parent,
+            result: TraceResult::Pending,
+            events: vec![],
+        });
+        self.obligations.len() - 1
+    }
+
+    /// Records the outcome of processing the obligation at `index`, and
+    /// its predicate with the type variables resolved so far.
+    pub fn record<'tcx>(&mut self,
+                        index: usize,
+                        obligation: &PredicateObligation<'tcx>,
+                        result: TraceResult,
+                        events: Vec<SelectionEvent>) {
+        let traced = &mut self.obligations[index];
+        traced.predicate = obligation.predicate.to_string();
+        traced.result = result;
+        traced.events.extend(events);
+    }
+
+    /// Writes the trace to its file, reporting I/O errors as compiler errors.
+    pub fn dump(&self, tcx: TyCtxt) {
+        let json = tcx.sess.opts.debugging_opts.dump_obligations_format.as_ref()
+                      .map_or(false, |format| format == "json");
+        let file_name = format!("rustc.node{}.obligations.{}.{}",
+                                self.item_id, self.disambiguator,
+                                if json { "json" } else { "dot" });
+        let result = File::create(&file_name).and_then(|mut file| {
+            if json {
+                writeln!(file, "{}", json::as_pretty_json(self))
+            } else {
+                dot::render(self, &mut file)
+            }
+        });
+        if let Err(e) = result {
+            tcx.sess.err(&format!("couldn't write obligation trace `{}`: {}", file_name, e));
+        }
+    }
+}
+
+impl<'a> dot::Labeller<'a> for ObligationTrace {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn graph_id(&self) -> dot::Id {
+        dot::Id::new(format!("obligations_node{}", self.item_id)).unwrap()
+    }
+
+    fn node_id(&self, n: &usize) -> dot::Id {
+        dot::Id::new(format!("obligation_{}", n)).unwrap()
+    }
+
+    fn node_label(&self, n: &usize) -> dot::LabelText {
+        let obligation = &self.obligations[*n];
+        let mut label = format!("{}\n{}\n{:?}", obligation.predicate, obligation.span,
+                                obligation.result);
+        let depth = obligation.events.iter().map(|event| event.depth).min().unwrap_or(0);
+        for event in &obligation.events {
+            let indent: String = iter::repeat("  ").take(event.depth - depth).collect();
+            label.push_str(&format!("\n{}{:?}: {}", indent, event.kind, event.detail));
+        }
+        dot::LabelText::label(label)
+    }
+
+    fn node_shape(&self, _: &usize) -> Option<dot::LabelText> {
+        Some(dot::LabelText::label("box"))
+    }
+
+    fn node_style(&self, n: &usize) -> dot::Style {
+        match self.obligations[*n].result {
+            TraceResult::Pending | TraceResult::Ambiguous => dot::Style::Dashed,
+            TraceResult::Proven => dot::Style::None,
+            TraceResult::Error(_) => dot::Style::Bold,
+        }
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for ObligationTrace {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn nodes(&self) -> dot::Nodes<usize> {
+        (0..self.obligations.len()).collect::<Vec<_>>().into_cow()
+    }
+
+    fn edges(&self) -> dot::Edges<(usize, usize)> {
+        self.obligations.iter().enumerate().filter_map(|(i, obligation)| {
+            obligation.parent.map(|parent| (parent, i))
+        }).collect::<Vec<_>>().into_cow()
+    }
+
+    fn source(&self, edge: &(usize, usize)) -> usize {
+        edge.0
+    }
+
+    fn target(&self, edge: &(usize, usize)) -> usize {
+        edge.1
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `-Z apply-suggestions`: the code suggestions attached to the
+//! diagnostics of a compilation are either written back to the source files
+//! or printed as a unified diff.
+
+use rustc::session::Session;
+use rustc::session::config::ErrorOutputType;
+use rustc::session::early_error;
+use syntax::errors::fix;
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::thread;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FixMode {
+    /// Rewrite the source files.
+    InPlace,
+    /// Print a diff to stdout.
+    Diff,
+}
+
+impl FixMode {
+    pub fn from_opt(opt: &Option<String>, error_format: ErrorOutputType) -> Option<FixMode> {
+        match opt.as_ref().map(|s| &s[..]) {
+            None => None,
+            Some("inplace") => Some(FixMode::InPlace),
+            Some("diff") => Some(FixMode::Diff),
+            Some(s) => {
+                early_error(error_format,
+                            &format!("unknown value `{}` for `-Z apply-suggestions`, \
+                                      expected `inplace` or `diff`", s))
+            }
+        }
+    }
+}
+
+/// Applies the suggestions collected by the session's handler.
+pub fn apply_suggestions(sess: &Session, mode: FixMode) {
+    let suggestions = sess.diagnostic().take_suggestions();
+    let fixes = fix::resolve_suggestions(sess.codemap(), &suggestions);
+
+    for file in fixes.files.values() {
+        match mode {
+            FixMode::Diff => print!("{}", file.diff()),
+            FixMode::InPlace => write_file(sess, file),
+        }
+    }
+
+    if fixes.skipped > 0 {
+        sess.note_without_error(&format!("{} suggestion(s) could not be applied \
+                                          automatically", fixes.skipped));
+    }
+}
+
+/// Reports, when compilation is aborted by a fatal error before
+/// `apply_suggestions` gets to run, that no suggestion has been applied.
+pub struct AbortNotice<'a>(pub &'a Session);
+
+impl<'a> Drop for AbortNotice<'a> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.note_without_error("compilation was aborted, so no suggestion has been \
+                                       applied");
+        }
+    }
+}
+
+fn write_file(sess: &Session, file: &fix::FileFix) {
+    // The edits are relative to the source as it was read by the compiler;
+    // don't clobber the file if it has been modified since (or if the
+    // compiler normalized it on loading, e.g. by stripping a BOM).
+    let mut current = String::new();
+    if let Err(e) = File::open(&file.file_name).and_then(|mut f| f.read_to_string(&mut current)) {
+        sess.err(&format!("failed to read `{}`: {}", file.file_name, e));
+        return
+    }
+    if current != *file.src {
+        sess.warn(&format!("not applying suggestions to `{}`: the file has changed",
+                           file.file_name));
+        return
+    }
+
+    let fixed = file.apply();
+    match File::create(&file.file_name).and_then(|mut f| f.write_all(fixed.as_bytes())) {
+        Ok(()) => {
+            sess.note_without_error(&format!("applied {} edit(s) to `{}`",
+                                             file.edits.len(), file.file_name));
+        }
+        Err(e) => sess.err(&format!("failed to write `{}`: {}", file.file_name, e)),
+    }
+}
@@ -102,6 +102,7 @@ use syntax::parse::{self, PResult, token};
 pub mod test;
 
 pub mod driver;
+pub mod fix;
 pub mod pretty;
 pub mod target_features;
 
@@ -175,6 +176,8 @@ pub fn run_compiler_with_file_loader<'a, L>(args: &[String],
     };
 
     let sopts = config::build_session_options(&matches);
+    let fix_mode = fix::FixMode::from_opt(&sopts.debugging_opts.apply_suggestions,
+                                          sopts.error_format);
 
     if sopts.debugging_opts.debug_llvm {
         unsafe { llvm::LLVMSetDebug(1); }
@@ -207,6 +210,9 @@ pub fn run_compiler_with_file_loader<'a, L>(args: &[String],
                                                    cstore.clone(),
                                                    codemap);
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    if fix_mode.is_some() {
+        sess.diagnostic().collect_suggestions();
+    }
     let mut cfg = config::build_configuration(&sess);
     target_features::add_configuration(&mut cfg, &sess);
 
@@ -214,9 +220,15 @@ pub fn run_compiler_with_file_loader<'a, L>(args: &[String],
 
     let plugins = sess.opts.debugging_opts.extra_plugins.clone();
     let control = callbacks.build_controller(&sess, &matches);
-    (driver::compile_input(&sess, &cstore, cfg, &input, &odir, &ofile,
-                           Some(plugins), &control),
-     Some(sess))
+    let result = {
+        let _notice = fix_mode.map(|_| fix::AbortNotice(&sess));
+        driver::compile_input(&sess, &cstore, cfg, &input, &odir, &ofile,
+                              Some(plugins), &control)
+    };
+    if let Some(mode) = fix_mode {
+        fix::apply_suggestions(&sess, mode);
+    }
+    (result, Some(sess))
 }
 
 // Extract output directory and file from matches.
@@ -370,31 +382,37 @@ fn handle_explain(code: &str,
 
 fn check_cfg(sopts: &config::Options,
              output: ErrorOutputType) {
+    let invalid_predicates = sopts.cfg.iter().filter_map(|item| {
+        match item.node {
+            ast::MetaItemKind::List(ref pred, _) => Some(pred),
+            _ => None,
+        }
+    }).collect::<Vec<_>>();
+    if invalid_predicates.is_empty() {
+        return
+    }
+
+    // Only create the emitter now: the SARIF emitter writes out its document
+    // when it is dropped, even if it is empty.
     let mut emitter: Box<Emitter> = match output {
         config::ErrorOutputType::HumanReadable(color_config) => {
             Box::new(errors::emitter::BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(errors::json::JsonEmitter::basic()),
-    };
-
-    let mut saw_invalid_predicate = false;
-    for item in sopts.cfg.iter() {
-        match item.node {
-            ast::MetaItemKind::List(ref pred, _) => {
-                saw_invalid_predicate = true;
-                emitter.emit(&MultiSpan::new(),
-                             &format!("invalid predicate in --cfg command line argument: `{}`",
-                                      pred),
-                             None,
-                             errors::Level::Fatal);
-            }
-            _ => {},
+        config::ErrorOutputType::Short => {
+            Box::new(errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Never))
         }
+        config::ErrorOutputType::Sarif => Box::new(errors::sarif::SarifEmitter::basic()),
+    };
+    for pred in invalid_predicates {
+        emitter.emit(&MultiSpan::new(),
+                     &format!("invalid predicate in --cfg command line argument: `{}`",
+                              pred),
+                     None,
+                     errors::Level::Fatal);
     }
-
-    if saw_invalid_predicate {
-        panic!(errors::FatalError);
-    }
+    drop(emitter);
+    panic!(errors::FatalError);
 }
 
 impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
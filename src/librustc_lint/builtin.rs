@@ -45,7 +45,8 @@ use std::collections::HashSet;
 
 use syntax::{ast};
 use syntax::attr::{self, AttrMetaMethods};
-use syntax::codemap::{self, Span};
+use syntax::codemap::{self, BytePos, Span};
+use syntax::errors::DiagnosticBuilder;
 
 use rustc::hir::{self, PatKind};
 use rustc::hir::intravisit::FnKind;
@@ -590,19 +591,44 @@ impl Deprecated {
         // Deprecated attributes apply in-crate and cross-crate.
         if let Some(&attr::Stability{rustc_depr: Some(attr::RustcDeprecation{ref reason, ..}), ..})
                 = *stability {
-            output(cx, DEPRECATED, span, Some(&reason))
-        } else if let Some(attr::Deprecation{ref note, ..}) = *deprecation {
-            output(cx, DEPRECATED, span, note.as_ref().map(|x| &**x))
+            output(cx, DEPRECATED, span, Some(&reason)).emit()
+        } else if let Some(attr::Deprecation{ref note, ref suggestion, ..}) = *deprecation {
+            let mut err = output(cx, DEPRECATED, span, note.as_ref().map(|x| &**x));
+            if let Some(ref suggestion) = *suggestion {
+                suggest_replacement(cx, span, suggestion, &mut err);
+            }
+            err.emit()
         }
 
-        fn output(cx: &LateContext, lint: &'static Lint, span: Span, note: Option<&str>) {
+        fn output<'a>(cx: &'a LateContext, lint: &'static Lint, span: Span, note: Option<&str>)
+                      -> DiagnosticBuilder<'a> {
             let msg = if let Some(note) = note {
                 format!("use of deprecated item: {}", note)
             } else {
                 format!("use of deprecated item")
             };
 
-            cx.span_lint(lint, span, &msg);
+            cx.struct_span_lint(lint, span, &msg)
+        }
+
+        /// Suggests replacing the name the deprecated item is referred to
+        /// by, if `span` ends with it (it doesn't after a turbofish, for
+        /// example).
+        fn suggest_replacement(cx: &LateContext, span: Span, replacement: &str,
+                               err: &mut DiagnosticBuilder) {
+            let snippet = match cx.sess().codemap().span_to_snippet(span) {
+                Ok(snippet) => snippet,
+                Err(_) => return,
+            };
+            let name_len = snippet.chars().rev()
+                                  .take_while(|&c| c.is_alphanumeric() || c == '_')
+                                  .fold(0, |len, c| len + c.len_utf8());
+            if name_len == 0 {
+                return;
+            }
+            let name_span = Span { lo: span.hi - BytePos(name_len as u32), ..span };
+            err.span_applicable_suggestion(name_span, "use the replacement:",
+                                           replacement.to_string());
         }
     }
 }
@@ -45,6 +45,7 @@ extern crate rustc;
 extern crate log;
 extern crate rustc_back;
 extern crate rustc_const_eval;
+extern crate rustc_const_math;
 
 pub use rustc::lint as lint;
 pub use rustc::middle as middle;
@@ -57,11 +58,13 @@ use lint::FutureIncompatibleInfo;
 
 mod bad_style;
 mod builtin;
+mod perf;
 mod types;
 mod unused;
 
 use bad_style::*;
 use builtin::*;
+use perf::*;
 use types::*;
 use unused::*;
 
@@ -128,10 +131,13 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                  PluginAsLibrary,
                  DropWithReprExtern,
                  MutableTransmutes,
+                 Clones,
+                 NeedlessBorrow,
                  );
 
     add_builtin_with_new!(sess,
                           TypeLimits,
+                          ConstArithmetic,
                           MissingDoc,
                           MissingDebugImplementations,
                           );
@@ -144,6 +150,9 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                     UNUSED_MUT, UNREACHABLE_CODE, UNUSED_MUST_USE,
                     UNUSED_UNSAFE, PATH_STATEMENTS, UNUSED_ATTRIBUTES);
 
+    add_lint_group!(sess, "perf",
+                    CLONE_ON_COPY, REDUNDANT_CLONE, NEEDLESS_BORROW);
+
     // Guidelines for creating a future incompatibility lint:
     //
     // - Create a lint defaulting to warn as normal, with ideally the same error
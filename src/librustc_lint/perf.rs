@@ -0,0 +1,339 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lints for code which does more work than it needs to: clones which could
+//! be copies or moves, and borrows which are immediately undone by the
+//! compiler. They are all allowed by default, and can be enabled together
+//! with the `perf` lint group.
+
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, TyCtxt, TypeFoldable};
+use rustc::ty::adjustment;
+use util::nodemap::FnvHashSet;
+use lint::{LateContext, LintContext, LintArray};
+use lint::{LintPass, LateLintPass};
+
+use syntax::ast;
+use syntax::codemap::{Span, NO_EXPANSION};
+
+use rustc::hir;
+use rustc::hir::intravisit::{self, FnKind, Visitor};
+
+declare_lint! {
+    pub CLONE_ON_COPY,
+    Allow,
+    "calls to `clone` on values of a `Copy` type"
+}
+
+declare_lint! {
+    pub REDUNDANT_CLONE,
+    Allow,
+    "clones of local variables which are not used afterwards"
+}
+
+declare_lint! {
+    pub NEEDLESS_BORROW,
+    Allow,
+    "borrows of references which are immediately dereferenced by the compiler"
+}
+
+/// Checks calls to `Clone::clone`.
+///
+/// A clone is redundant if it is the last use of a local variable, in which
+/// case the variable can be moved instead. This is only checked outside of
+/// loops and closures, where the textual order of the uses of a variable is
+/// the order in which they happen, and for variables which are never
+/// borrowed (a borrow could outlive the clone).
+#[derive(Copy, Clone)]
+pub struct Clones;
+
+impl LintPass for Clones {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(CLONE_ON_COPY, REDUNDANT_CLONE)
+    }
+}
+
+impl LateLintPass for Clones {
+    fn check_fn(&mut self,
+                cx: &LateContext,
+                fk: FnKind,
+                _: &hir::FnDecl,
+                body: &hir::Block,
+                _: Span,
+                id: ast::NodeId) {
+        // The body of a closure is checked along with the enclosing function.
+        if let FnKind::Closure(..) = fk {
+            return;
+        }
+
+        let mut visitor = CloneVisitor {
+            tcx: cx.tcx,
+            param_env: ty::ParameterEnvironment::for_item(cx.tcx, id),
+            loop_depth: 0,
+            closure_depth: 0,
+            uses: Vec::new(),
+            borrows: FnvHashSet(),
+            candidates: Vec::new(),
+            clones_on_copy: Vec::new(),
+        };
+        visitor.visit_block(body);
+
+        for (span, copy) in visitor.clones_on_copy {
+            let mut err = cx.struct_span_lint(CLONE_ON_COPY, span,
+                                              "using `clone` on a `Copy` type; the value can \
+                                               be copied instead");
+            if let Some(copy) = copy {
+                err.span_applicable_suggestion(span, "copy it:", copy);
+            }
+            err.emit();
+        }
+
+        for &(ref candidate, ref receiver) in &visitor.candidates {
+            let used_again = visitor.uses.iter().any(|u| {
+                u.var == candidate.var && u.expr != candidate.expr &&
+                (u.span.lo > candidate.span.hi || visitor.borrows.contains(&u.expr))
+            });
+            if !used_again {
+                let mut err = cx.struct_span_lint(REDUNDANT_CLONE, candidate.span,
+                                                  "redundant clone: the variable is not used \
+                                                   afterwards, so it can be moved instead");
+                if let Ok(snippet) = cx.sess().codemap().span_to_snippet(*receiver) {
+                    err.span_applicable_suggestion(candidate.span, "move it:", snippet);
+                }
+                err.emit();
+            }
+        }
+    }
+}
+
+/// A use of a local variable.
+struct VarUse {
+    /// The variable.
+    var: ast::NodeId,
+    /// The path expression using it.
+    expr: ast::NodeId,
+    span: Span,
+}
+
+struct CloneVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParameterEnvironment<'tcx>,
+    /// The number of loops and closures we are in.
+    loop_depth: usize,
+    /// The number of closures we are in.
+    closure_depth: usize,
+    uses: Vec<VarUse>,
+    /// The path expressions which borrow the variable they refer to.
+    borrows: FnvHashSet<ast::NodeId>,
+    /// Clones of local variables which may be redundant, along with the
+    /// span of their receiver; `expr` is the receiver of the call.
+    candidates: Vec<(VarUse, Span)>,
+    /// Clones of `Copy` values, along with the expression copying the value
+    /// instead, if there is one.
+    clones_on_copy: Vec<(Span, Option<String>)>,
+}
+
+impl<'a, 'tcx> CloneVisitor<'a, 'tcx> {
+    fn local_var(&self, expr: &hir::Expr) -> Option<ast::NodeId> {
+        if let hir::ExprPath(..) = expr.node {
+            match self.tcx.def_map.borrow().get(&expr.id).map(|d| d.full_def()) {
+                Some(Def::Local(_, id)) | Some(Def::Upvar(_, id, _, _)) => Some(id),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Records the path expression at the base of the lvalue `expr` as a
+    /// borrow of its variable.
+    fn borrow_base(&mut self, mut expr: &hir::Expr) {
+        loop {
+            match expr.node {
+                hir::ExprField(ref base, _) |
+                hir::ExprTupField(ref base, _) |
+                hir::ExprIndex(ref base, _) => expr = base,
+                hir::ExprPath(..) => {
+                    self.borrows.insert(expr.id);
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn check_clone(&mut self, expr: &hir::Expr, receiver: &hir::Expr) {
+        let callee = match self.tcx.tables.borrow().method_map
+                                              .get(&ty::MethodCall::expr(expr.id)) {
+            Some(callee) => callee.def_id,
+            None => return,
+        };
+        if !is_clone_method(self.tcx, callee) {
+            return;
+        }
+
+        // `Clone::clone` returns `Self`.
+        let ty = self.tcx.expr_ty(expr);
+        if !ty.moves_by_default(self.tcx, &self.param_env, expr.span) {
+            let copy = self.copy_of(receiver, ty);
+            self.clones_on_copy.push((expr.span, copy));
+            return;
+        }
+
+        // Only a clone of the variable itself (rather than of something it
+        // points to) can be replaced by a move.
+        let autoderefs = match self.tcx.tables.borrow().adjustments.get(&receiver.id) {
+            Some(&adjustment::AdjustDerefRef(ref adj)) => adj.autoderefs,
+            _ => 0,
+        };
+        if self.loop_depth == 0 && autoderefs == 0 {
+            if let Some(var) = self.local_var(receiver) {
+                let clone = VarUse { var: var, expr: receiver.id, span: expr.span };
+                self.candidates.push((clone, receiver.span));
+            }
+        }
+    }
+
+    /// The source of an expression copying the value `receiver` is cloned
+    /// from, which is `receiver` itself or what it refers to.
+    fn copy_of(&self, receiver: &hir::Expr, ty: ty::Ty<'tcx>) -> Option<String> {
+        let snippet = match self.tcx.sess.codemap().span_to_snippet(receiver.span) {
+            Ok(snippet) => snippet,
+            Err(_) => return None,
+        };
+        let receiver_ty = self.tcx.expr_ty(receiver);
+        if receiver_ty == ty {
+            return Some(snippet);
+        }
+        match receiver_ty.sty {
+            // A method call receiver binds tighter than a dereference, so it
+            // never needs parentheses.
+            ty::TyRef(_, ref mt) if mt.ty == ty => Some(format!("*{}", snippet)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for CloneVisitor<'a, 'tcx> {
+    fn visit_expr(&mut self, expr: &'v hir::Expr) {
+        if let Some(var) = self.local_var(expr) {
+            self.uses.push(VarUse { var: var, expr: expr.id, span: expr.span });
+            // A closure may be called after the clone.
+            if self.closure_depth > 0 {
+                self.borrows.insert(expr.id);
+            }
+        }
+
+        match expr.node {
+            hir::ExprAddrOf(_, ref inner) => self.borrow_base(inner),
+            // `ref` bindings borrow the scrutinee.
+            hir::ExprMatch(ref scrutinee, ref arms, _) => {
+                if arms.iter().any(|arm| self.tcx.arm_contains_ref_binding(arm).is_some()) {
+                    self.borrow_base(scrutinee);
+                }
+            }
+            hir::ExprMethodCall(_, _, ref args) => {
+                // The receiver stays borrowed if the result may hold on to
+                // the borrow.
+                let autoref = match self.tcx.tables.borrow().adjustments.get(&args[0].id) {
+                    Some(&adjustment::AdjustDerefRef(ref adj)) => adj.autoref.is_some(),
+                    _ => false,
+                };
+                if autoref && self.tcx.expr_ty(expr).has_erasable_regions() {
+                    self.borrow_base(&args[0]);
+                }
+                if expr.span.expn_id == NO_EXPANSION {
+                    self.check_clone(expr, &args[0]);
+                }
+            }
+            _ => {}
+        }
+
+        match expr.node {
+            hir::ExprLoop(..) | hir::ExprWhile(..) => {
+                self.loop_depth += 1;
+                intravisit::walk_expr(self, expr);
+                self.loop_depth -= 1;
+            }
+            hir::ExprClosure(..) => {
+                self.loop_depth += 1;
+                self.closure_depth += 1;
+                intravisit::walk_expr(self, expr);
+                self.closure_depth -= 1;
+                self.loop_depth -= 1;
+            }
+            _ => intravisit::walk_expr(self, expr),
+        }
+    }
+
+    fn visit_local(&mut self, local: &'v hir::Local) {
+        // `ref` bindings borrow the initializer.
+        if let Some(ref init) = local.init {
+            if self.tcx.pat_contains_ref_binding(&local.pat).is_some() {
+                self.borrow_base(init);
+            }
+        }
+        intravisit::walk_local(self, local);
+    }
+}
+
+fn is_clone_method(tcx: TyCtxt, def_id: DefId) -> bool {
+    if tcx.item_name(def_id).as_str() != "clone" {
+        return false;
+    }
+    match tcx.trait_of_item(def_id) {
+        Some(trait_def_id) => {
+            ty::item_path::with_forced_absolute_paths(|| tcx.item_path_str(trait_def_id)) ==
+                "core::clone::Clone"
+        }
+        None => false,
+    }
+}
+
+/// Checks for `&expr` where `expr` already is a reference and the result is
+/// dereferenced and borrowed again by an adjustment, as in `f(&s)` with
+/// `s: &str` and `f` taking a `&str`.
+#[derive(Copy, Clone)]
+pub struct NeedlessBorrow;
+
+impl LintPass for NeedlessBorrow {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(NEEDLESS_BORROW)
+    }
+}
+
+impl LateLintPass for NeedlessBorrow {
+    fn check_expr(&mut self, cx: &LateContext, e: &hir::Expr) {
+        if e.span.expn_id != NO_EXPANSION {
+            return;
+        }
+        let inner = match e.node {
+            hir::ExprAddrOf(hir::MutImmutable, ref inner) => inner,
+            _ => return,
+        };
+        if let ty::TyRef(..) = cx.tcx.expr_ty(inner).sty {
+            if let Some(&adjustment::AdjustDerefRef(ref adj)) =
+                    cx.tcx.tables.borrow().adjustments.get(&e.id) {
+                // The outer reference is removed by the second dereference.
+                if adj.autoderefs > 1 && adj.autoref.is_some() {
+                    let mut err = cx.struct_span_lint(NEEDLESS_BORROW, e.span,
+                                                      "this expression borrows a reference \
+                                                       that is immediately dereferenced by \
+                                                       the compiler");
+                    if let Ok(snippet) = cx.sess().codemap().span_to_snippet(inner.span) {
+                        err.span_applicable_suggestion(e.span, "remove the borrow:", snippet);
+                    }
+                    err.emit();
+                }
+            }
+        }
+    }
+}
@@ -14,9 +14,11 @@ use rustc::hir::def_id::DefId;
 use rustc::ty::subst::Substs;
 use rustc::ty::{self, Ty, TyCtxt};
 use middle::const_val::ConstVal;
-use rustc_const_eval::eval_const_expr_partial;
+use middle::const_qualif::ConstQualif;
+use rustc_const_eval::{eval_const_expr_partial, ConstEvalErr, ErrKind};
 use rustc_const_eval::EvalHint::ExprTypeChecked;
-use util::nodemap::{FnvHashSet};
+use rustc_const_math::ConstMathErr;
+use util::nodemap::{FnvHashSet, NodeMap};
 use lint::{LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass};
 
@@ -28,7 +30,7 @@ use syntax::abi::Abi;
 use syntax::attr;
 use syntax::codemap::{self, Span};
 
-use rustc::hir;
+use rustc::hir::{self, PatKind};
 
 register_long_diagnostics! {
 E0519: r##"
@@ -355,6 +357,122 @@ impl LateLintPass for TypeLimits {
     }
 }
 
+declare_lint! {
+    OVERFLOWING_ARITHMETIC,
+    Warn,
+    "arithmetic on constant operands which overflows or divides by zero"
+}
+
+/// Evaluates integer arithmetic whose operands are constant, or immutable
+/// local variables initialized with a constant, and warns about operations
+/// which are certain to fail at runtime.
+pub struct ConstArithmetic {
+    /// The values of immutable local variables initialized with a constant
+    /// integer, by the id of their binding.
+    locals: NodeMap<ConstVal>,
+}
+
+impl ConstArithmetic {
+    pub fn new() -> ConstArithmetic {
+        ConstArithmetic {
+            locals: NodeMap(),
+        }
+    }
+}
+
+impl LintPass for ConstArithmetic {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(OVERFLOWING_ARITHMETIC)
+    }
+}
+
+impl LateLintPass for ConstArithmetic {
+    fn check_local(&mut self, cx: &LateContext, l: &hir::Local) {
+        let init = match l.init {
+            Some(ref init) => init,
+            None => return,
+        };
+        if let PatKind::Binding(hir::BindByValue(hir::MutImmutable), _, None) = l.pat.node {
+            let val = eval_const_expr_partial(cx.tcx, init, ExprTypeChecked, Some(&self.locals));
+            if let Ok(val @ ConstVal::Integral(_)) = val {
+                self.locals.insert(l.pat.id, val);
+            }
+        }
+    }
+
+    fn check_expr(&mut self, cx: &LateContext, e: &hir::Expr) {
+        let operands = match e.node {
+            hir::ExprBinary(binop, ref l, ref r) => {
+                match binop.node {
+                    hir::BiAdd | hir::BiSub | hir::BiMul | hir::BiDiv | hir::BiRem => {}
+                    hir::BiShl | hir::BiShr => {
+                        // Shifts by a constant amount are checked by
+                        // `exceeding_bitshifts`.
+                        if eval_const_expr_partial(cx.tcx, r, ExprTypeChecked, None).is_ok() {
+                            return;
+                        }
+                    }
+                    _ => return,
+                }
+                vec![&**l, &**r]
+            }
+            hir::ExprUnary(hir::UnNeg, ref operand) => {
+                // Negated literals are checked by `overflowing_literals`.
+                if let hir::ExprLit(_) = operand.node {
+                    return;
+                }
+                vec![&**operand]
+            }
+            _ => return,
+        };
+
+        match cx.tcx.node_id_to_type(e.id).sty {
+            ty::TyInt(_) | ty::TyUint(_) => {}
+            _ => return,
+        }
+
+        // Expressions which are constant by themselves have already been
+        // evaluated (and reported by `const_err`) by the constness checks.
+        if let Some(qualif) = cx.tcx.const_qualif_map.borrow().get(&e.id) {
+            if !qualif.intersects(ConstQualif::NOT_CONST) {
+                return;
+            }
+        }
+
+        // Only report the innermost failing operation.
+        let eval = |e: &hir::Expr| {
+            eval_const_expr_partial(cx.tcx, e, ExprTypeChecked, Some(&self.locals))
+        };
+        if operands.iter().any(|operand| eval(*operand).is_err()) {
+            return;
+        }
+
+        if let Err(err) = eval(e) {
+            if is_arithmetic_error(&err) {
+                cx.span_lint(OVERFLOWING_ARITHMETIC, e.span,
+                             &format!("this operation will fail at runtime: {}",
+                                      err.description()));
+            }
+        }
+
+        fn is_arithmetic_error(err: &ConstEvalErr) -> bool {
+            match err.kind {
+                ErrKind::DivideByZero |
+                ErrKind::DivideWithOverflow |
+                ErrKind::ModuloByZero |
+                ErrKind::ModuloWithOverflow |
+                ErrKind::ShiftLeftWithOverflow |
+                ErrKind::ShiftRightWithOverflow |
+                ErrKind::Math(ConstMathErr::Overflow(_)) |
+                ErrKind::Math(ConstMathErr::DivisionByZero) |
+                ErrKind::Math(ConstMathErr::RemainderByZero) |
+                ErrKind::Math(ConstMathErr::ShiftNegative) => true,
+                _ => false,
+            }
+        }
+    }
+}
+
 declare_lint! {
     IMPROPER_CTYPES,
     Warn,
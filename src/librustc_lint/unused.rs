@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
 use rustc::hir::pat_util;
 use rustc::ty;
 use rustc::ty::adjustment;
@@ -101,7 +103,7 @@ impl LateLintPass for UnusedMut {
 declare_lint! {
     pub UNUSED_MUST_USE,
     Warn,
-    "unused result of a type flagged as #[must_use]"
+    "unused result of a type or function flagged as #[must_use]"
 }
 
 declare_lint! {
@@ -130,6 +132,27 @@ impl LateLintPass for UnusedResults {
             return;
         }
 
+        // The return value of a function or method flagged as #[must_use]
+        // must be used, whatever its type.
+        if let Some(def_id) = callee_def_id(cx, expr) {
+            let msg = format!("unused return value of `{}` which must be used",
+                              cx.tcx.item_path_str(def_id));
+            let attrs = cx.tcx.get_attrs(def_id);
+            if check_must_use(cx, &attrs[..], s.span, msg.clone()) {
+                return;
+            }
+            // Implementations of a trait method inherit its #[must_use].
+            if let Some(trait_item) = cx.tcx.trait_item_of_item(def_id) {
+                let trait_item = trait_item.def_id();
+                if trait_item != def_id {
+                    let attrs = cx.tcx.get_attrs(trait_item);
+                    if check_must_use(cx, &attrs[..], s.span, msg) {
+                        return;
+                    }
+                }
+            }
+        }
+
         let t = cx.tcx.expr_ty(&expr);
         let warned = match t.sty {
             ty::TyTuple(ref tys) if tys.is_empty() => return,
@@ -137,7 +160,8 @@ impl LateLintPass for UnusedResults {
             ty::TyStruct(def, _) |
             ty::TyEnum(def, _) => {
                 let attrs = cx.tcx.get_attrs(def.did);
-                check_must_use(cx, &attrs[..], s.span)
+                check_must_use(cx, &attrs[..], s.span,
+                               "unused result which must be used".to_string())
             }
             _ => false,
         };
@@ -145,10 +169,31 @@ impl LateLintPass for UnusedResults {
             cx.span_lint(UNUSED_RESULTS, s.span, "unused result");
         }
 
-        fn check_must_use(cx: &LateContext, attrs: &[ast::Attribute], sp: Span) -> bool {
+        // The function or method called by `expr`, if any.
+        fn callee_def_id(cx: &LateContext, expr: &hir::Expr) -> Option<DefId> {
+            match expr.node {
+                hir::ExprCall(ref callee, _) => {
+                    match cx.tcx.def_map.borrow().get(&callee.id).map(|d| d.full_def()) {
+                        Some(Def::Fn(def_id)) | Some(Def::Method(def_id)) => Some(def_id),
+                        _ => None,
+                    }
+                }
+                hir::ExprMethodCall(..) => {
+                    cx.tcx.tables.borrow().method_map
+                          .get(&ty::MethodCall::expr(expr.id))
+                          .map(|callee| callee.def_id)
+                }
+                _ => None,
+            }
+        }
+
+        fn check_must_use(cx: &LateContext,
+                          attrs: &[ast::Attribute],
+                          sp: Span,
+                          mut msg: String)
+                          -> bool {
             for attr in attrs {
                 if attr.check_name("must_use") {
-                    let mut msg = "unused result which must be used".to_string();
                     // check for #[must_use="..."]
                     match attr.value_str() {
                         None => {}
@@ -2135,9 +2135,10 @@ fn enum_variant_size_lint(ccx: &CrateContext, enum_def: &hir::EnumDef, sp: Span,
         }
     }
 
-    // we only warn if the largest variant is at least thrice as large as
-    // the second-largest.
-    if !is_allow && largest > slargest * 3 && slargest > 0 {
+    // we only warn if the largest variant is at least `factor` times (by
+    // default thrice) as large as the second-largest.
+    let factor = if is_allow { 3 } else { variant_size_factor(ccx.sess()) };
+    if !is_allow && largest > slargest * factor && slargest > 0 {
         // Use lint::raw_emit_lint rather than sess.add_lint because the lint-printing
         // pass for the latter already ran.
         lint::raw_struct_lint(&ccx.tcx().sess,
@@ -2145,15 +2146,35 @@ fn enum_variant_size_lint(ccx: &CrateContext, enum_def: &hir::EnumDef, sp: Span,
                               lint::builtin::VARIANT_SIZE_DIFFERENCES,
                               *lvlsrc.unwrap(),
                               Some(sp),
-                              &format!("enum variant is more than three times larger ({} bytes) \
+                              &format!("enum variant is more than {} times larger ({} bytes) \
                                         than the next largest (ignoring padding)",
-                                       largest))
+                                       factor, largest))
             .span_note(enum_def.variants[largest_index].span,
                        "this variant is the largest")
             .emit();
     }
 }
 
+/// The `factor` option of the `variant_size_differences` lint, as given in
+/// the lint configuration file.
+fn variant_size_factor(sess: &Session) -> u64 {
+    let lints = sess.lint_store.borrow();
+    match lints.lint_option(lint::builtin::VARIANT_SIZE_DIFFERENCES, "factor") {
+        None => 3,
+        Some(factor) => {
+            match factor.parse() {
+                Ok(factor) if factor > 0 => factor,
+                _ => {
+                    sess.err(&format!("invalid `factor` option of the \
+                                       `variant_size_differences` lint: `{}`, expected a \
+                                       positive integer", factor));
+                    3
+                }
+            }
+        }
+    }
+}
+
 pub fn llvm_linkage_by_name(name: &str) -> Option<Linkage> {
     // Use the names from src/llvm/docs/LangRef.rst here. Most types are only
     // applicable to variable declarations and may not really make sense for
@@ -1082,20 +1082,22 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
                 match (&ty.node, full_span) {
                     (&hir::TyRptr(None, ref mut_ty), Some(full_span)) => {
                         let mutbl_str = if mut_ty.mutbl == hir::MutMutable { "mut " } else { "" };
-                        err.span_suggestion(full_span, "try adding parentheses (per RFC 438):",
-                                            format!("&{}({} +{})",
-                                                    mutbl_str,
-                                                    pprust::ty_to_string(&mut_ty.ty),
-                                                    pprust::bounds_to_string(bounds)));
+                        err.span_applicable_suggestion(full_span,
+                                                       "try adding parentheses (per RFC 438):",
+                                                       format!("&{}({} +{})",
+                                                               mutbl_str,
+                                                               pprust::ty_to_string(&mut_ty.ty),
+                                                               pprust::bounds_to_string(bounds)));
                     }
                     (&hir::TyRptr(Some(ref lt), ref mut_ty), Some(full_span)) => {
                         let mutbl_str = if mut_ty.mutbl == hir::MutMutable { "mut " } else { "" };
-                        err.span_suggestion(full_span, "try adding parentheses (per RFC 438):",
-                                            format!("&{} {}({} +{})",
-                                                    pprust::lifetime_to_string(lt),
-                                                    mutbl_str,
-                                                    pprust::ty_to_string(&mut_ty.ty),
-                                                    pprust::bounds_to_string(bounds)));
+                        err.span_applicable_suggestion(full_span,
+                                                       "try adding parentheses (per RFC 438):",
+                                                       format!("&{} {}({} +{})",
+                                                               pprust::lifetime_to_string(lt),
+                                                               mutbl_str,
+                                                               pprust::ty_to_string(&mut_ty.ty),
+                                                               pprust::bounds_to_string(bounds)));
                     }
 
                     _ => {
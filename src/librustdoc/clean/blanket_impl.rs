@@ -0,0 +1,172 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Discovery of blanket impls which apply to local types.
+//!
+//! A blanket impl such as `impl<T: Display> ToString for T` never shows up in
+//! the list of impls of any type, since its `Self` type is a type parameter.
+//! This module asks the trait selection machinery which of the blanket impls
+//! defined in the crates we depend on hold for each local type, so that they
+//! can be listed on that type's page.
+
+use std::collections::HashSet;
+
+use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::infer::TypeOrigin;
+use rustc::middle::cstore;
+use rustc::traits::{self, ProjectionMode};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::subst::{self, Subst};
+
+use syntax::ast;
+use syntax::codemap::DUMMY_SP;
+
+use core::{DocContext, DocAccessLevels};
+use clean::{self, inline};
+
+use super::Clean;
+
+/// Returns one impl item for every blanket impl from another crate which
+/// holds for the local type definition `id`.
+///
+/// The returned impls have their `for_` set to the local type and carry the
+/// original `Self` type of the impl in `blanket_impl`.
+pub fn build_blanket_impls(cx: &DocContext, id: ast::NodeId) -> Vec<clean::Item> {
+    let tcx = match cx.tcx_opt() {
+        Some(tcx) => tcx,
+        None => return Vec::new(),
+    };
+    let did = cx.map.local_def_id(id);
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+    let item_ty = tcx.lookup_item_type(did).ty;
+    let self_ty = item_ty.subst(tcx, param_env.free_substs);
+    let for_ = item_ty.clean(cx);
+
+    blanket_impl_candidates(cx, tcx).into_iter().filter(|&impl_did| {
+        impl_applies(tcx, &param_env, self_ty, impl_did)
+    }).map(|impl_did| {
+        build_blanket_impl(cx, tcx, impl_did, for_.clone())
+    }).collect()
+}
+
+/// Collects the blanket impls of all external crates whose trait is
+/// reachable in the generated documentation. The list is computed once and
+/// then cached in the `DocContext`.
+fn blanket_impl_candidates<'a, 'tcx>(cx: &DocContext,
+                                     tcx: TyCtxt<'a, 'tcx, 'tcx>) -> Vec<DefId> {
+    if let Some(ref impls) = *cx.blanket_impl_candidates.borrow() {
+        return impls.clone();
+    }
+
+    let mut impls = Vec::new();
+    for cnum in tcx.sess.cstore.crates() {
+        for item in tcx.sess.cstore.crate_top_level_items(cnum) {
+            collect_blanket_impls(cx, tcx, item.def, &mut impls);
+        }
+    }
+    debug!("blanket_impl_candidates: {:?}", impls);
+
+    *cx.blanket_impl_candidates.borrow_mut() = Some(impls.clone());
+    impls
+}
+
+fn collect_blanket_impls<'a, 'tcx>(cx: &DocContext,
+                                   tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   def: cstore::DefLike,
+                                   impls: &mut Vec<DefId>) {
+    match def {
+        cstore::DlImpl(did) => {
+            let trait_ref = match tcx.impl_trait_ref(did) {
+                Some(trait_ref) => trait_ref,
+                None => return,
+            };
+            if tcx.sess.cstore.is_default_impl(did) ||
+               tcx.trait_impl_polarity(did) == Some(hir::ImplPolarity::Negative) {
+                return
+            }
+            if let ty::TyParam(..) = trait_ref.self_ty().sty {
+                if cx.access_levels.borrow().is_doc_reachable(trait_ref.def_id) {
+                    impls.push(did);
+                }
+            }
+        }
+        cstore::DlDef(Def::Mod(did)) => {
+            for item in tcx.sess.cstore.item_children(did) {
+                collect_blanket_impls(cx, tcx, item.def, impls)
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Does the blanket impl `impl_did` hold for `self_ty`, given the bounds in
+/// `param_env`? This unifies the `Self` type of the impl with `self_ty` and
+/// then evaluates every `where` clause of the impl.
+fn impl_applies<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          param_env: &ty::ParameterEnvironment<'tcx>,
+                          self_ty: Ty<'tcx>,
+                          impl_did: DefId) -> bool {
+    tcx.infer_ctxt(None, Some(param_env.clone()), ProjectionMode::AnyFinal).enter(|infcx| {
+        let mut selcx = traits::SelectionContext::new(&infcx);
+        let header = ty::ImplHeader::with_fresh_ty_vars(&mut selcx, impl_did);
+
+        if let Err(_) = infcx.eq_types(true,
+                                       TypeOrigin::Misc(DUMMY_SP),
+                                       header.self_ty,
+                                       self_ty) {
+            return false;
+        }
+
+        let applies = header.predicates.iter().all(|predicate| {
+            let predicate = infcx.resolve_type_vars_if_possible(predicate);
+            let obligation = traits::Obligation::new(traits::ObligationCause::dummy(),
+                                                     predicate);
+            selcx.evaluate_obligation(&obligation)
+        });
+        debug!("impl_applies(impl_did={:?}, self_ty={:?}) = {}",
+               impl_did, self_ty, applies);
+        applies
+    })
+}
+
+fn build_blanket_impl<'a, 'tcx>(cx: &DocContext,
+                                tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                impl_did: DefId,
+                                for_: clean::Type) -> clean::Item {
+    let ty = tcx.lookup_item_type(impl_did);
+    let predicates = tcx.lookup_predicates(impl_did);
+    let trait_ = match tcx.impl_trait_ref(impl_did).unwrap().clean(cx) {
+        clean::TraitBound(polyt, _) => polyt.trait_,
+        clean::RegionBound(..) => unreachable!(),
+    };
+
+    clean::Item {
+        inner: clean::ImplItem(clean::Impl {
+            unsafety: hir::Unsafety::Normal, // FIXME: this should be decoded
+            generics: (&ty.generics, &predicates, subst::TypeSpace).clean(cx),
+            provided_trait_methods: HashSet::new(),
+            trait_: Some(trait_),
+            for_: for_,
+            items: Vec::new(),
+            derived: false,
+            polarity: None,
+            blanket_impl: Some(ty.ty.clean(cx)),
+        }),
+        source: clean::Span::empty(),
+        name: None,
+        attrs: inline::load_attrs(cx, tcx, impl_did),
+        visibility: Some(clean::Inherited),
+        stability: tcx.lookup_stability(impl_did).clean(cx),
+        deprecation: tcx.lookup_deprecation(impl_did).clean(cx),
+        def_id: impl_did,
+    }
+}
@@ -432,6 +432,7 @@ pub fn build_impl<'a, 'tcx>(cx: &DocContext,
             generics: (&ty.generics, &predicates, subst::TypeSpace).clean(cx),
             items: trait_items,
             polarity: polarity.map(|p| { p.clean(cx) }),
+            blanket_impl: None,
         }),
         source: clean::Span::empty(),
         name: None,
@@ -60,8 +60,11 @@ use visit_ast;
 use html::item_type::ItemType;
 
 pub mod inline;
+mod blanket_impl;
 mod simplify;
 
+use self::blanket_impl::build_blanket_impls;
+
 // extract the stability index for a node from tcx, if possible
 fn get_stability(cx: &DocContext, def_id: DefId) -> Option<Stability> {
     cx.tcx_opt().and_then(|tcx| tcx.lookup_stability(def_id)).clean(cx)
@@ -401,6 +404,8 @@ impl Clean<Item> for doctree::Module {
         items.extend(self.imports.iter().flat_map(|x| x.clean(cx)));
         items.extend(self.structs.iter().map(|x| x.clean(cx)));
         items.extend(self.enums.iter().map(|x| x.clean(cx)));
+        items.extend(self.structs.iter().flat_map(|x| build_blanket_impls(cx, x.id)));
+        items.extend(self.enums.iter().flat_map(|x| build_blanket_impls(cx, x.id)));
         items.extend(self.fns.iter().map(|x| x.clean(cx)));
         items.extend(self.foreigns.iter().flat_map(|x| x.clean(cx)));
         items.extend(self.mods.iter().map(|x| x.clean(cx)));
@@ -2241,6 +2246,9 @@ pub struct Impl {
     pub items: Vec<Item>,
     pub derived: bool,
     pub polarity: Option<ImplPolarity>,
+    /// The `Self` type of a blanket impl (`impl<T: Bound> Trait for T`) from
+    /// another crate that was found to hold for `for_`.
+    pub blanket_impl: Option<Type>,
 }
 
 fn detect_derived<M: AttrMetaMethods>(attrs: &[M]) -> bool {
@@ -2285,6 +2293,7 @@ impl Clean<Vec<Item>> for doctree::Impl {
                 items: items,
                 derived: detect_derived(&self.attrs),
                 polarity: Some(self.polarity.clean(cx)),
+                blanket_impl: None,
             }),
         });
         ret
@@ -64,6 +64,8 @@ pub struct DocContext<'a, 'tcx: 'a> {
     pub renderinfo: RefCell<RenderInfo>,
     /// Later on moved through `clean::Crate` into `html::render::CACHE_KEY`
     pub external_traits: RefCell<HashMap<DefId, clean::Trait>>,
+    /// Blanket impls from external crates, computed on first use
+    pub blanket_impl_candidates: RefCell<Option<Vec<DefId>>>,
 }
 
 impl<'b, 'tcx> DocContext<'b, 'tcx> {
@@ -196,6 +198,7 @@ pub fn run_core(search_paths: SearchPaths,
             access_levels: RefCell::new(access_levels),
             external_traits: RefCell::new(HashMap::new()),
             renderinfo: RefCell::new(Default::default()),
+            blanket_impl_candidates: RefCell::new(None),
         };
         debug!("crate: {:?}", ctxt.map.krate());
 
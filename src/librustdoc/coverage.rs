@@ -0,0 +1,224 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Documentation coverage statistics, as printed by `--show-coverage`.
+//!
+//! The statistics are gathered from the cleaned crate after all passes have
+//! run, so with the default passes only public items are taken into account.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::iter::repeat;
+
+use serialize::json::{ToJson, Json};
+
+use clean::{self, Item};
+use fold::DocFolder;
+
+/// Number of items in a module, along with how many of them are documented
+/// and how many of them have a code example in their documentation.
+#[derive(Default, Copy, Clone, PartialEq, Debug)]
+pub struct ItemCount {
+    pub total: u64,
+    pub with_docs: u64,
+    pub with_examples: u64,
+}
+
+impl ItemCount {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
+        self.total += 1;
+        if has_docs {
+            self.with_docs += 1;
+        }
+        if has_examples {
+            self.with_examples += 1;
+        }
+    }
+
+    fn add(&mut self, other: &ItemCount) {
+        self.total += other.total;
+        self.with_docs += other.with_docs;
+        self.with_examples += other.with_examples;
+    }
+
+    fn percentage(&self, n: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            n as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+impl ToJson for ItemCount {
+    fn to_json(&self) -> Json {
+        let mut data = BTreeMap::new();
+        data.insert("total".to_owned(), self.total.to_json());
+        data.insert("with_docs".to_owned(), self.with_docs.to_json());
+        data.insert("with_examples".to_owned(), self.with_examples.to_json());
+        Json::Object(data)
+    }
+}
+
+/// Coverage of a whole crate, keyed by the full path of each module.
+pub struct Coverage {
+    pub krate: String,
+    pub modules: BTreeMap<String, ItemCount>,
+}
+
+impl Coverage {
+    pub fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for count in self.modules.values() {
+            total.add(count);
+        }
+        total
+    }
+
+    pub fn print_table(&self, w: &mut Write) -> io::Result<()> {
+        let width = self.modules.keys()
+                                .map(|m| m.len())
+                                .chain(Some("Total".len()))
+                                .max()
+                                .unwrap_or(0);
+        let rule = repeat("-").take(width + 44).collect::<String>();
+
+        writeln!(w, "{:<width$} {:>8} {:>10} {:>7} {:>8} {:>7}",
+                 "Module", "Items", "Documented", "%", "Examples", "%", width = width)?;
+        writeln!(w, "{}", rule)?;
+        for (module, count) in &self.modules {
+            print_row(w, module, count, width)?;
+        }
+        writeln!(w, "{}", rule)?;
+        print_row(w, "Total", &self.total(), width)
+    }
+
+    pub fn print_json(&self, w: &mut Write) -> io::Result<()> {
+        writeln!(w, "{}", self.to_json())
+    }
+}
+
+impl ToJson for Coverage {
+    fn to_json(&self) -> Json {
+        let modules = self.modules.iter().map(|(module, count)| {
+            (module.clone(), count.to_json())
+        }).collect();
+
+        let mut data = BTreeMap::new();
+        data.insert("crate".to_owned(), self.krate.to_json());
+        data.insert("modules".to_owned(), Json::Object(modules));
+        data.insert("total".to_owned(), self.total().to_json());
+        Json::Object(data)
+    }
+}
+
+fn print_row(w: &mut Write, name: &str, count: &ItemCount, width: usize) -> io::Result<()> {
+    writeln!(w, "{:<width$} {:>8} {:>10} {:>6.1}% {:>8} {:>6.1}%",
+             name,
+             count.total,
+             count.with_docs,
+             count.percentage(count.with_docs),
+             count.with_examples,
+             count.percentage(count.with_examples),
+             width = width)
+}
+
+/// Walks the crate and counts documented items per module.
+pub fn calculate(krate: clean::Crate) -> Coverage {
+    let mut calc = CoverageCalculator {
+        krate: krate.name.clone(),
+        modules: BTreeMap::new(),
+        path: Vec::new(),
+    };
+    calc.fold_crate(krate);
+    Coverage { krate: calc.krate, modules: calc.modules }
+}
+
+struct CoverageCalculator {
+    krate: String,
+    modules: BTreeMap<String, ItemCount>,
+    /// Path of the module currently being visited.
+    path: Vec<String>,
+}
+
+impl DocFolder for CoverageCalculator {
+    fn fold_item(&mut self, i: Item) -> Option<Item> {
+        let (skip, count) = match i.inner {
+            // Stripped items don't show up in the documentation at all, and
+            // items of trait impls get their documentation from the trait.
+            clean::StrippedItem(..) => (true, false),
+            clean::ImplItem(ref impl_) if impl_.trait_.is_some() => (true, false),
+            // Tuple struct fields can't be documented individually.
+            clean::StructFieldItem(..) if i.name.is_none() => (true, false),
+            clean::ImplItem(..) |
+            clean::ExternCrateItem(..) |
+            clean::ImportItem(..) => (false, false),
+            _ => (false, true),
+        };
+        if skip {
+            return Some(i);
+        }
+
+        if count {
+            if i.is_crate() {
+                self.path.push(self.krate.clone());
+            } else if i.is_mod() {
+                self.path.push(i.name.clone().unwrap_or(String::new()));
+            }
+            let (has_docs, has_examples) = match i.doc_value() {
+                Some(doc) => (!doc.trim().is_empty(), has_code_example(doc)),
+                None => (false, false),
+            };
+            let module = self.path.join("::");
+            self.modules.entry(module).or_insert(ItemCount::default())
+                        .count_item(has_docs, has_examples);
+        }
+
+        let is_mod = i.is_mod();
+        let ret = self.fold_item_recur(i);
+        if is_mod {
+            self.path.pop();
+        }
+        ret
+    }
+}
+
+/// Does the documentation contain a fenced code block?
+fn has_code_example(doc: &str) -> bool {
+    doc.lines().any(|line| {
+        let line = line.trim_left();
+        line.starts_with("```") || line.starts_with("~~~")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_code_example, ItemCount};
+
+    #[test]
+    fn code_examples() {
+        assert!(has_code_example("Does things.\n\n```\nfoo();\n```"));
+        assert!(has_code_example("Does things.\n\n  ~~~rust\n  foo();\n  ~~~"));
+        assert!(!has_code_example("Does `things` with ``code``."));
+    }
+
+    #[test]
+    fn counts() {
+        let mut count = ItemCount::default();
+        assert_eq!(count.percentage(count.with_docs), 0.0);
+        count.count_item(true, true);
+        count.count_item(true, false);
+        count.count_item(false, false);
+        count.count_item(false, false);
+        assert_eq!(count, ItemCount { total: 4, with_docs: 2, with_examples: 1 });
+        assert_eq!(count.percentage(count.with_docs), 50.0);
+        assert_eq!(count.percentage(count.with_examples), 25.0);
+    }
+}
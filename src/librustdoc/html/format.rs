@@ -597,7 +597,10 @@ fn fmt_impl(i: &clean::Impl, f: &mut fmt::Formatter, link_trait: bool) -> fmt::R
         }
         write!(f, " for ")?;
     }
-    write!(f, "{}{}", i.for_, WhereClause(&i.generics))?;
+    match i.blanket_impl {
+        Some(ref ty) => write!(f, "{}{}", ty, WhereClause(&i.generics))?,
+        None => write!(f, "{}{}", i.for_, WhereClause(&i.generics))?,
+    }
     Ok(())
 }
 
@@ -125,7 +125,15 @@ r##"<!DOCTYPE html>
 
                 <p>
                     Search functions by type signature (e.g.
-                    <code>vec -> usize</code> or <code>* -> vec</code>)
+                    <code>vec -> usize</code>, <code>* -> vec</code> or
+                    <code>&amp;str -> Option&lt;T&gt;</code>). Single letters
+                    stand for any type.
+                </p>
+
+                <p>
+                    Searches of four or more characters also look through
+                    the item summaries. Items found by their summary alone are
+                    listed after those found by their name.
                 </p>
             </div>
         </div>
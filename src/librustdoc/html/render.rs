@@ -73,6 +73,7 @@ use html::format::fmt_impl_for_trait_page;
 use html::item_type::ItemType;
 use html::markdown::{self, Markdown};
 use html::{highlight, layout};
+use scrape_examples::ScrapedExamples;
 
 /// A pair of name and its optional document.
 pub type NameDoc = (String, Option<String>);
@@ -123,6 +124,9 @@ pub struct SharedContext {
     /// The given user css file which allow to customize the generated
     /// documentation theme.
     pub css_file_extension: Option<PathBuf>,
+    /// Calls to documented items collected from other crates, rendered below
+    /// the documentation of each item.
+    pub scraped_examples: ScrapedExamples,
 }
 
 /// Indicates where an external crate can be found.
@@ -338,8 +342,13 @@ impl ToJson for IndexItem {
 }
 
 /// A type used for the search index.
+///
+/// Only the last path segment of the type is kept, lowercased, together with
+/// its type parameters, described the same way (so `Option<Vec<&str>>`
+/// becomes `option<vec<str>>`).
 struct Type {
     name: Option<String>,
+    generics: Vec<Type>,
 }
 
 impl ToJson for Type {
@@ -348,6 +357,11 @@ impl ToJson for Type {
             Some(ref name) => {
                 let mut data = BTreeMap::new();
                 data.insert("name".to_owned(), name.to_json());
+                // Most types have no parameters, so leave the key out
+                // entirely to keep the index small.
+                if !self.generics.is_empty() {
+                    data.insert("generics".to_owned(), self.generics.to_json());
+                }
                 Json::Object(data)
             },
             None => Json::Null
@@ -396,6 +410,7 @@ fn init_ids() -> HashMap<String, usize> {
      "provided-methods",
      "implementors",
      "implementors-list",
+     "blanket-implementations",
      "methods",
      "deref-methods",
      "implementations",
@@ -438,7 +453,8 @@ pub fn run(mut krate: clean::Crate,
            dst: PathBuf,
            passes: HashSet<String>,
            css_file_extension: Option<PathBuf>,
-           renderinfo: RenderInfo) -> Result<(), Error> {
+           renderinfo: RenderInfo,
+           scraped_examples: ScrapedExamples) -> Result<(), Error> {
     let src_root = match krate.src.parent() {
         Some(p) => p.to_path_buf(),
         None => PathBuf::new(),
@@ -457,6 +473,7 @@ pub fn run(mut krate: clean::Crate,
             playground_url: "".to_string(),
         },
         css_file_extension: css_file_extension.clone(),
+        scraped_examples: scraped_examples,
     };
 
     // Crawl the crate attributes looking for attributes which control how we're
@@ -1022,9 +1039,11 @@ impl DocFolder for Cache {
                 self.traits.insert(item.def_id, t.clone());
             }
 
-            // Collect all the implementors of traits.
+            // Collect all the implementors of traits. Blanket impls that
+            // were instantiated for a local type are already listed on the
+            // trait's page in their generic form.
             if let clean::ImplItem(ref i) = item.inner {
-                if let Some(did) = i.trait_.def_id() {
+                if let (Some(did), None) = (i.trait_.def_id(), i.blanket_impl.as_ref()) {
                     self.implementors.entry(did).or_insert(vec![]).push(Implementor {
                         def_id: item.def_id,
                         stability: item.stability.clone(),
@@ -1655,7 +1674,25 @@ fn document(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item) -> fmt::Re
     if let Some(s) = item.doc_value() {
         write!(w, "<div class='docblock'>{}</div>", Markdown(s))?;
     }
-    Ok(())
+    render_scraped_examples(w, cx, item)
+}
+
+fn render_scraped_examples(w: &mut fmt::Formatter, cx: &Context,
+                           item: &clean::Item) -> fmt::Result {
+    let examples = match cx.shared.scraped_examples.get(&item.def_id) {
+        Some(examples) if !examples.is_empty() => examples,
+        _ => return Ok(()),
+    };
+    write!(w, "<div class='scraped-examples'><h4>Usage examples</h4>")?;
+    for example in examples {
+        write!(w, "<div class='scraped-example'>\
+                   <div class='scraped-example-title'>{}:{}</div>{}</div>",
+               Escape(&example.file), example.call_line,
+               highlight::render_with_highlighting(&example.code,
+                                                   Some("rust-example-rendered"),
+                                                   None))?;
+    }
+    write!(w, "</div>")
 }
 
 fn document_short(w: &mut fmt::Formatter, item: &clean::Item, link: AssocItemLink) -> fmt::Result {
@@ -2472,6 +2509,9 @@ fn render_assoc_items(w: &mut fmt::Formatter,
     let (non_trait, traits): (Vec<_>, _) = v.iter().partition(|i| {
         i.inner_impl().trait_.is_none()
     });
+    let (blanket, traits): (Vec<&Impl>, Vec<&Impl>) = traits.into_iter().partition(|i| {
+        i.inner_impl().blanket_impl.is_some()
+    });
     if !non_trait.is_empty() {
         let render_header = match what {
             AssocItemRender::All => {
@@ -2521,6 +2561,30 @@ fn render_assoc_items(w: &mut fmt::Formatter,
             }
         }
     }
+    if !blanket.is_empty() {
+        write!(w, "<h2 id='blanket-implementations'>Blanket \
+                   Implementations</h2>")?;
+        for i in &blanket {
+            render_blanket_impl(w, i, containing_item.stable_since())?;
+        }
+    }
+    Ok(())
+}
+
+/// Blanket impls only get their header rendered, in its generic form
+/// (`impl<T: Display> ToString for T`); the items of the impl are documented
+/// in the crate that defines it.
+fn render_blanket_impl(w: &mut fmt::Formatter, i: &Impl,
+                       outer_version: Option<&str>) -> fmt::Result {
+    write!(w, "<h3 class='impl'><span class='in-band'><code>{}</code>", i.inner_impl())?;
+    write!(w, "</span><span class='out-of-band'>")?;
+    let since = i.impl_item.stability.as_ref().map(|s| &s.since[..]);
+    render_stability_since_raw(w, since, outer_version)?;
+    write!(w, "</span>")?;
+    write!(w, "</h3>\n")?;
+    if let Some(ref dox) = i.impl_item.attrs.value("doc") {
+        write!(w, "<div class='docblock'>{}</div>", Markdown(dox))?;
+    }
     Ok(())
 }
 
@@ -2797,6 +2861,8 @@ fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
 
     let inputs = decl.inputs.values.iter().map(|arg| get_index_type(&arg.type_)).collect();
     let output = match decl.output {
+        // `-> ()` is indexed the same as no return type at all.
+        clean::FunctionRetTy::Return(clean::Tuple(ref tys)) if tys.is_empty() => None,
         clean::FunctionRetTy::Return(ref return_type) => Some(get_index_type(return_type)),
         _ => None
     };
@@ -2805,7 +2871,10 @@ fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
 }
 
 fn get_index_type(clean_type: &clean::Type) -> Type {
-    Type { name: get_index_type_name(clean_type).map(|s| s.to_ascii_lowercase()) }
+    Type {
+        name: get_index_type_name(clean_type).map(|s| s.to_ascii_lowercase()),
+        generics: get_index_type_generics(clean_type),
+    }
 }
 
 fn get_index_type_name(clean_type: &clean::Type) -> Option<String> {
@@ -2815,11 +2884,43 @@ fn get_index_type_name(clean_type: &clean::Type) -> Option<String> {
             Some(segments[segments.len() - 1].name.clone())
         },
         clean::Generic(ref s) => Some(s.clone()),
-        clean::Primitive(ref p) => Some(format!("{:?}", p)),
-        clean::BorrowedRef { ref type_, .. } => get_index_type_name(type_),
-        // FIXME: add all from clean::Type.
-        _ => None
-    }
+        clean::Primitive(ref p) => Some(p.to_string().to_owned()),
+        clean::BorrowedRef { ref type_, .. } |
+        clean::RawPointer(_, ref type_) |
+        clean::Unique(ref type_) => get_index_type_name(type_),
+        clean::Vector(_) => Some(clean::Slice.to_string().to_owned()),
+        clean::FixedVector(..) => Some(clean::Array.to_string().to_owned()),
+        clean::Tuple(_) => Some(clean::PrimitiveTuple.to_string().to_owned()),
+        clean::QPath { ref name, .. } => Some(name.clone()),
+        clean::BareFunction(_) => Some("fn".to_owned()),
+        clean::Bottom => Some("!".to_owned()),
+        clean::Infer | clean::PolyTraitRef(_) => None,
+    }
+}
+
+/// Returns the type parameters of `clean_type` as search index types, e.g.
+/// `t` for `Option<T>` and `u8` for `&[u8]`.
+fn get_index_type_generics(clean_type: &clean::Type) -> Vec<Type> {
+    let params = match *clean_type {
+        clean::ResolvedPath { ref path, .. } => {
+            match path.segments.last().map(|s| &s.params) {
+                Some(&clean::PathParameters::AngleBracketed { ref types, .. }) => {
+                    types.iter().collect()
+                }
+                _ => Vec::new(),
+            }
+        }
+        clean::BorrowedRef { ref type_, .. } |
+        clean::RawPointer(_, ref type_) |
+        clean::Unique(ref type_) => return get_index_type_generics(type_),
+        clean::Vector(ref t) | clean::FixedVector(ref t, _) => vec![&**t],
+        clean::Tuple(ref types) => types.iter().collect(),
+        _ => Vec::new(),
+    };
+    params.into_iter()
+          .map(get_index_type)
+          .filter(|t| t.name.is_some())
+          .collect()
 }
 
 pub fn cache() -> Arc<Cache> {
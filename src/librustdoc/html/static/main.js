@@ -207,6 +207,7 @@
     function initSearch(rawSearchIndex) {
         var currentResults, index, searchIndex;
         var MAX_LEV_DISTANCE = 3;
+        var MIN_FULL_TEXT_LENGTH = 4;
         var params = getQueryStringParams();
 
         // Populate search bar with query string search term when provided,
@@ -217,6 +218,168 @@
             $(".search-input")[0].value = params.search || '';
         }
 
+        /**
+         * Splits a comma-separated list of types, ignoring the commas that
+         * appear inside of generic parameters (`a, b<c, d>` is two types).
+         * @param  {[string]} list [The list of types]
+         * @return {[Array]}       [The individual types, trimmed]
+         */
+        function splitTypeList(list) {
+            var types = [], depth = 0, start = 0;
+            for (var i = 0; i < list.length; ++i) {
+                var c = list.charAt(i);
+                if (c === "<") {
+                    depth += 1;
+                } else if (c === ">") {
+                    depth -= 1;
+                } else if (c === "," && depth === 0) {
+                    types.push(list.substring(start, i));
+                    start = i + 1;
+                }
+            }
+            types.push(list.substring(start));
+            return types.map(function(s) { return s.trim(); })
+                        .filter(function(s) { return s !== ""; });
+        }
+
+        /**
+         * Normalizes a type written in a query the same way rustdoc
+         * normalizes types in the search index: references, pointers and
+         * `mut` are dropped, slices and arrays become `slice`/`array`, and
+         * only the last path segment is kept.
+         * @param  {[string]} raw [The type as typed by the user]
+         * @return {[Object]}     [An object with `name` and `generics`]
+         */
+        function parseType(raw) {
+            var ty = raw.trim().replace(/^(&\s*|\*\s*(const|mut)\s+|mut\s+)+/, "");
+            var generics = [];
+
+            if (ty.charAt(0) === "[" && ty.charAt(ty.length - 1) === "]") {
+                var inner = ty.substring(1, ty.length - 1);
+                var isArray = inner.indexOf(";") > -1;
+                return {name: isArray ? "array" : "slice",
+                        generics: [parseType(inner.split(";")[0])]};
+            }
+            if (ty.charAt(0) === "(" && ty.charAt(ty.length - 1) === ")") {
+                return {name: "tuple",
+                        generics: splitTypeList(ty.substring(1, ty.length - 1))
+                                  .map(parseType)};
+            }
+
+            var lt = ty.indexOf("<");
+            if (lt > -1 && ty.charAt(ty.length - 1) === ">") {
+                generics = splitTypeList(ty.substring(lt + 1, ty.length - 1))
+                           .map(parseType);
+                ty = ty.substring(0, lt);
+            }
+            var segments = ty.split("::");
+            return {name: segments[segments.length - 1].trim(), generics: generics};
+        }
+
+        /**
+         * Single letters in a query (`Option<T>`, lowercased like the rest
+         * of the query by then) stand for any type, as does `*`.
+         */
+        function isWildcardType(name) {
+            return name === "*" || /^[a-z]$/.test(name);
+        }
+
+        /**
+         * Computes how far a query type is from a type in the search index.
+         * @param  {[Object]} query [The parsed query type]
+         * @param  {[Object]} type  [The type from the search index]
+         * @return {[number]}       [The distance, or MAX_LEV_DISTANCE + 1 if
+         *                           the types do not match at all]
+         */
+        function checkType(query, type) {
+            var nomatch = MAX_LEV_DISTANCE + 1;
+            if (isWildcardType(query.name)) {
+                return 0;
+            }
+            // Types the index couldn't describe are stored as `null`.
+            if (!type || !type.name) {
+                return nomatch;
+            }
+            // Be more strict with short names, "str" should not match "u8".
+            var maxLev = Math.min(MAX_LEV_DISTANCE, Math.floor(query.name.length / 3));
+            var lev = levenshtein(query.name, type.name);
+            if (lev > maxLev) {
+                return nomatch;
+            }
+
+            var typeGenerics = type.generics || [];
+            for (var i = 0; i < query.generics.length; ++i) {
+                var generic = query.generics[i];
+                if (isWildcardType(generic.name)) {
+                    if (typeGenerics.length === 0) {
+                        return nomatch;
+                    }
+                    continue;
+                }
+                var best = nomatch;
+                for (var j = 0; j < typeGenerics.length; ++j) {
+                    best = Math.min(best, checkType(generic, typeGenerics[j]));
+                }
+                if (best === nomatch) {
+                    return nomatch;
+                }
+                lev += best;
+            }
+            return lev;
+        }
+
+        /**
+         * Checks whether a function type from the search index satisfies a
+         * `inputs -> output` query. Every input of the query has to match a
+         * distinct argument of the function, in any order; the function may
+         * take further arguments. An empty output only matches functions
+         * that return nothing.
+         * @return {[number]} [The total distance of the match, or
+         *                     MAX_LEV_DISTANCE + 1 if there is none]
+         */
+        function checkSignature(inputs, output, type) {
+            var nomatch = MAX_LEV_DISTANCE + 1;
+            var total = 0;
+
+            if (output.name === "") {
+                if (type.output) {
+                    return nomatch;
+                }
+            } else if (!isWildcardType(output.name)) {
+                if (!type.output) {
+                    return nomatch;
+                }
+                total += checkType(output, type.output);
+                if (total > MAX_LEV_DISTANCE) {
+                    return nomatch;
+                }
+            }
+
+            var used = [];
+            for (var i = 0; i < inputs.length; ++i) {
+                var best = nomatch, bestIdx = -1;
+                for (var j = 0; j < type.inputs.length; ++j) {
+                    if (used[j]) {
+                        continue;
+                    }
+                    var lev = checkType(inputs[i], type.inputs[j]);
+                    if (lev < best) {
+                        best = lev;
+                        bestIdx = j;
+                    }
+                }
+                if (bestIdx < 0) {
+                    return nomatch;
+                }
+                used[bestIdx] = true;
+                total += best;
+                if (total > MAX_LEV_DISTANCE) {
+                    return nomatch;
+                }
+            }
+            return total;
+        }
+
         /**
          * Executes the query and builds an index of results
          * @param  {[Object]} query     [The user query]
@@ -281,12 +444,15 @@
                 }
             // searching by type
             } else if (val.search("->") > -1) {
-                var trimmer = function (s) { return s.trim(); };
-                var parts = val.split("->").map(trimmer);
-                var input = parts[0];
-                // sort inputs so that order does not matter
-                var inputs = input.split(",").map(trimmer).sort().toString();
-                var output = parts[1];
+                var parts = val.split("->");
+                // `*` as an input means "any arguments", which is what an
+                // empty list of inputs already matches
+                var inputs = splitTypeList(parts[0]).map(parseType).filter(function(t) {
+                    return t.name !== "*";
+                });
+                var rawOutput = parts.slice(1).join("->").trim();
+                // `-> ()` is indexed the same as no return type at all
+                var output = parseType(rawOutput === "()" ? "" : rawOutput);
 
                 for (var i = 0; i < nSearchWords; ++i) {
                     var type = searchIndex[i].type;
@@ -294,16 +460,11 @@
                         continue;
                     }
 
-                    // sort index inputs so that order does not matter
-                    var typeInputs = type.inputs.map(function (input) {
-                        return input.name;
-                    }).sort();
-
-                    // allow searching for void (no output) functions as well
-                    var typeOutput = type.output ? type.output.name : "";
-                    if ((inputs === "*" || inputs === typeInputs.toString()) &&
-                        (output === "*" || output == typeOutput)) {
-                        results.push({id: i, index: -1, dontValidate: true});
+                    var lev = checkSignature(inputs, output, type);
+                    if (lev <= MAX_LEV_DISTANCE) {
+                        if (typePassesFilter(typeFilter, searchIndex[i].ty)) {
+                            results.push({id: i, index: -1, lev: lev, dontValidate: true});
+                        }
                     }
                 }
             } else {
@@ -335,6 +496,19 @@
                                     lev: lev_distance,
                                 });
                             }
+                        } else if (val.length >= MIN_FULL_TEXT_LENGTH &&
+                                   searchIndex[j].desc &&
+                                   searchIndex[j].desc.toLowerCase().indexOf(valLower) > -1) {
+                            // full-text matches on the summary line go after
+                            // every match on the item name
+                            if (typePassesFilter(typeFilter, searchIndex[j].ty)) {
+                                results.push({
+                                    id: j,
+                                    index: -1,
+                                    lev: MAX_LEV_DISTANCE + 1,
+                                    dontValidate: true,
+                                });
+                            }
                         }
                         if (results.length === max) {
                             break;
@@ -701,7 +875,9 @@
                 //              (String) full path or empty string for previous path,
                 //              (String) description,
                 //              (Number | null) the parent path index to `paths`]
-                //              (Object | null) the type of the function (if any)
+                //              (Object | null) the type of the function (if any),
+                //                              as {inputs: [type], output: type | null}
+                //                              where a type is {name, generics?}
                 var items = rawSearchIndex[crate].items;
                 // an array of [(Number) item type,
                 //              (String) name]
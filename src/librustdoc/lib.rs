@@ -51,6 +51,7 @@ extern crate serialize as rustc_serialize; // used by deriving
 use std::collections::HashMap;
 use std::default::Default;
 use std::env;
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::process;
 use std::sync::mpsc::channel;
@@ -64,6 +65,7 @@ pub mod externalfiles;
 
 pub mod clean;
 pub mod core;
+pub mod coverage;
 pub mod doctree;
 pub mod fold;
 pub mod html {
@@ -79,6 +81,7 @@ pub mod html {
 pub mod markdown;
 pub mod passes;
 pub mod plugins;
+pub mod scrape_examples;
 pub mod visit_ast;
 pub mod visit_lib;
 pub mod test;
@@ -179,6 +182,14 @@ pub fn opts() -> Vec<RustcOptGroup> {
         unstable(optopt("e", "extend-css",
                         "to redefine some css rules with a given file to generate doc with your \
                          own theme", "PATH")),
+        unstable(optflag("", "show-coverage",
+                         "calculate the percentage of public items with documentation \
+                          and code examples instead of generating documentation; \
+                          use `--output-format json` for machine-readable output")),
+        unstable(optmulti("", "scrape-examples",
+                          "save-analysis JSON files of crates (e.g. examples or tests) to \
+                           collect calls to documented items from",
+                          "PATH")),
         unstable(optmulti("Z", "",
                           "internal and debugging options (only on nightly build)", "FLAG")),
     )
@@ -268,6 +279,19 @@ pub fn main_args(args: &[String]) -> isize {
         }
     }
 
+    // Check the format before spending time on the crate.
+    let show_coverage = matches.opt_present("show-coverage");
+    if show_coverage {
+        match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("json") | Some("html") | None => {}
+            Some(s) => {
+                let _ = writeln!(&mut io::stderr(),
+                                 "unknown output format for --show-coverage: {}", s);
+                return 1;
+            }
+        }
+    }
+
     let external_html = match ExternalHtml::load(
             &matches.opt_strs("html-in-header"),
             &matches.opt_strs("html-before-content"),
@@ -298,6 +322,27 @@ pub fn main_args(args: &[String]) -> isize {
         }
     };
     let Output { krate, passes, renderinfo } = out;
+
+    if show_coverage {
+        let coverage = coverage::calculate(krate);
+        let mut stdout = io::stdout();
+        let res = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("json") => coverage.print_json(&mut stdout),
+            _ => coverage.print_table(&mut stdout),
+        };
+        res.expect("failed to print documentation coverage");
+        return 0;
+    }
+
+    let scraped_examples = match scrape_examples::load(&krate.name,
+                                                       &matches.opt_strs("scrape-examples")) {
+        Ok(examples) => examples,
+        Err(err) => {
+            println!("failed to load examples: {}", err);
+            return 1;
+        }
+    };
+
     info!("going to format");
     match matches.opt_str("w").as_ref().map(|s| &**s) {
         Some("html") | None => {
@@ -305,7 +350,8 @@ pub fn main_args(args: &[String]) -> isize {
                               output.unwrap_or(PathBuf::from("doc")),
                               passes.into_iter().collect(),
                               css_file_extension,
-                              renderinfo)
+                              renderinfo,
+                              scraped_examples)
                 .expect("failed to generate documentation")
         }
         Some(s) => {
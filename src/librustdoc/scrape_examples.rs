@@ -0,0 +1,171 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Collection of usage examples from other crates (`--scrape-examples`).
+//!
+//! The crates to take examples from (typically the contents of `examples/`
+//! and `tests/`) are compiled separately with `-Z save-analysis`, and the
+//! resulting JSON files are handed to rustdoc. Every function or method call
+//! recorded there whose target is an item of the documented crate becomes an
+//! example snippet, rendered below the documentation of that item.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use rustc::hir::def_id::{DefId, DefIndex};
+use rustc::middle::cstore::LOCAL_CRATE;
+use serialize::json::Json;
+
+/// Lines of context shown above and below each call.
+const CONTEXT_LINES: usize = 2;
+/// Only the first few call sites of an item are kept.
+const MAX_EXAMPLES_PER_ITEM: usize = 3;
+
+/// A snippet of code calling a documented item.
+#[derive(Clone, Debug)]
+pub struct ScrapedExample {
+    /// The file the call was found in, as recorded by save-analysis.
+    pub file: String,
+    /// The (1-based) line of the call.
+    pub call_line: usize,
+    /// The source lines around the call.
+    pub code: String,
+}
+
+pub type ScrapedExamples = HashMap<DefId, Vec<ScrapedExample>>;
+
+/// Loads the save-analysis files at `paths` and collects the calls they
+/// contain into items of the crate `krate`.
+pub fn load(krate: &str, paths: &[String]) -> Result<ScrapedExamples, String> {
+    let mut examples = HashMap::new();
+    let mut sources = HashMap::new();
+    for path in paths {
+        load_analysis(krate, Path::new(path), &mut examples, &mut sources)?;
+    }
+    Ok(examples)
+}
+
+fn load_analysis(krate: &str,
+                 path: &Path,
+                 examples: &mut ScrapedExamples,
+                 sources: &mut HashMap<PathBuf, Option<Vec<String>>>)
+                 -> Result<(), String> {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents))
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let analysis = Json::from_str(&contents)
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    // Find out which crate number the analyzed crate gave to ours; crates
+    // which don't use the documented crate have nothing to contribute.
+    let krate_num = analysis.find_path(&["prelude", "external_crates"])
+                            .and_then(|crates| crates.as_array())
+                            .and_then(|crates| crates.iter().find(|c| {
+                                c.find("name").and_then(|n| n.as_string()) == Some(krate)
+                            }))
+                            .and_then(|c| c.find("num"))
+                            .and_then(|n| n.as_u64());
+    let krate_num = match krate_num {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+
+    let refs = match analysis.find("refs").and_then(|r| r.as_array()) {
+        Some(refs) => refs,
+        None => return Ok(()),
+    };
+    let base = path.parent().unwrap_or(Path::new(""));
+    for r in refs {
+        if r.find("kind").and_then(|k| k.as_string()) != Some("Function") {
+            continue
+        }
+        let index = match (r.find_path(&["ref_id", "krate"]).and_then(|k| k.as_u64()),
+                           r.find_path(&["ref_id", "index"]).and_then(|i| i.as_u64())) {
+            (Some(k), Some(index)) if k == krate_num => index,
+            _ => continue,
+        };
+        let file = r.find_path(&["span", "file_name"]).and_then(|f| f.as_string());
+        let line = r.find_path(&["span", "line_start"]).and_then(|l| l.as_u64());
+        let (file, line) = match (file, line) {
+            (Some(file), Some(line)) => (file, line as usize),
+            _ => continue,
+        };
+
+        let did = DefId { krate: LOCAL_CRATE, index: DefIndex::from_u32(index as u32) };
+        let item_examples = examples.entry(did).or_insert(vec![]);
+        if item_examples.len() >= MAX_EXAMPLES_PER_ITEM ||
+           item_examples.iter().any(|e| e.file == file && e.call_line == line) {
+            continue
+        }
+
+        let source = resolve_source(base, file);
+        let lines = sources.entry(source.clone()).or_insert_with(|| read_lines(&source));
+        if let Some(ref lines) = *lines {
+            if let Some(code) = snippet(lines, line) {
+                item_examples.push(ScrapedExample {
+                    file: file.to_string(),
+                    call_line: line,
+                    code: code,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// File names in save-analysis data are relative to wherever the compiler
+/// was run; fall back to looking next to the analysis file.
+fn resolve_source(base: &Path, file: &str) -> PathBuf {
+    let path = PathBuf::from(file);
+    if path.is_absolute() || path.exists() {
+        path
+    } else {
+        base.join(path)
+    }
+}
+
+fn read_lines(path: &Path) -> Option<Vec<String>> {
+    let mut contents = String::new();
+    match File::open(path).and_then(|mut f| f.read_to_string(&mut contents)) {
+        Ok(_) => Some(contents.lines().map(|l| l.to_string()).collect()),
+        Err(e) => {
+            warn!("could not read example source {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Cuts the lines around the (1-based) line `line` out of `lines`.
+fn snippet(lines: &[String], line: usize) -> Option<String> {
+    if line == 0 || line > lines.len() {
+        return None;
+    }
+    let start = line.saturating_sub(CONTEXT_LINES + 1);
+    let end = cmp::min(lines.len(), line + CONTEXT_LINES);
+    Some(lines[start..end].join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::snippet;
+
+    #[test]
+    fn snippet_context() {
+        let lines: Vec<String> = (1..8).map(|i| format!("line{}", i)).collect();
+        assert_eq!(snippet(&lines, 4).unwrap(), "line2\nline3\nline4\nline5\nline6");
+        assert_eq!(snippet(&lines, 1).unwrap(), "line1\nline2\nline3");
+        assert_eq!(snippet(&lines, 7).unwrap(), "line5\nline6\nline7");
+        assert_eq!(snippet(&lines, 8), None);
+        assert_eq!(snippet(&lines, 0), None);
+    }
+}
@@ -118,6 +118,7 @@ pub fn run(input: &str,
         deref_trait_did: Cell::new(None),
         access_levels: Default::default(),
         renderinfo: Default::default(),
+        blanket_impl_candidates: RefCell::new(None),
     };
 
     let mut v = RustdocVisitor::new(&ctx);
@@ -17,14 +17,19 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use cmp;
 use fmt;
-use ffi::OsString;
+use ffi::{OsStr, OsString};
 use io::{self, SeekFrom, Seek, Read, Write};
 use path::{Path, PathBuf};
+use rand::{self, Rng};
+use sync::mpsc::{self, Receiver};
 use sys::fs as fs_imp;
+use vec;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
+use usize;
 use vec::Vec;
-use time::SystemTime;
+use time::{Duration, SystemTime};
 
 /// A reference to an open file on the filesystem.
 ///
@@ -151,6 +156,141 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// Options and flags which can be used to configure how a directory tree is
+/// walked.
+///
+/// This builder is created with `WalkOptions::new`, and the walk is started
+/// with `walk`. The `walk_dir` function walks a tree with the default
+/// options.
+///
+/// # Examples
+///
+/// Listing the Rust files at most two directories below `src`, in a stable
+/// order:
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs::WalkOptions;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let walk = try!(WalkOptions::new().max_depth(3).sort_by_file_name(true).walk("src"));
+/// for entry in walk {
+///     let path = try!(entry).path();
+///     if path.extension().map_or(false, |ext| ext == "rs") {
+///         println!("{}", path.display());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+pub struct WalkOptions {
+    min_depth: usize,
+    max_depth: usize,
+    follow_links: bool,
+    sort_by_file_name: bool,
+    contents_first: bool,
+}
+
+/// An iterator over the entries of a directory tree.
+///
+/// This iterator is returned from the `walk_dir` function and from
+/// `WalkOptions::walk`, and yields items of type `io::Result<DirEntry>`. An
+/// error for a single entry or directory does not end the walk, so the
+/// iterator may be resumed after it yields one.
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+pub struct WalkDir {
+    opts: WalkOptions,
+    stack: Vec<WalkDirLevel>,
+}
+
+/// Watches files and directory trees for changes, and sends events about
+/// them through a channel.
+///
+/// A watcher is created along with the `Receiver` of its events by
+/// `Watcher::new`, and paths are added to it with `watch`. Changes to a path
+/// are not reported right away, but held back for the delay given to `new`
+/// so that a burst of them is coalesced into a single event.
+///
+/// The watcher runs on a thread of its own, which stops when the watcher is
+/// dropped; the receiver then reports that the channel is closed.
+///
+/// # Platform-specific behavior
+///
+/// This is currently implemented with `inotify` on Linux, which is notified
+/// of changes as they happen. Each change is merged into the one pending for
+/// its path: a file which is created and then written to within the delay is
+/// reported as `Created`, and a file which is created and removed again is
+/// not reported at all.
+///
+/// Other platforms currently fall back to scanning the metadata of the
+/// watched paths every time the delay elapses, which only notices changes of
+/// the length or modification time of files. Changes are only coalesced when
+/// they fall between the same two scans, so a file created just before a
+/// scan and removed just after it is reported as both `Created` and
+/// `Removed`. Note that, this [may change in the future][changes].
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_watcher)]
+/// use std::fs::{Watcher, WatchEvent};
+/// use std::time::Duration;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let (watcher, events) = try!(Watcher::new(Duration::from_millis(100)));
+/// try!(watcher.watch("src", true));
+/// for event in events {
+///     match try!(event) {
+///         WatchEvent::Modified(path) => println!("{} changed", path.display()),
+///         WatchEvent::Rescan => println!("changes were lost"),
+///         _ => {}
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_watcher", issue = "0")]
+pub struct Watcher(fs_imp::Watcher);
+
+/// A change reported by a `Watcher`.
+///
+/// Each event holds the path of the file or directory which changed,
+/// made of the path given to `Watcher::watch` and the names of the entries
+/// below it. A rename is reported as the removal of the old path and the
+/// creation of the new one.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[unstable(feature = "fs_watcher", issue = "0")]
+pub enum WatchEvent {
+    /// A file or directory was created, or moved to the path.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Created(PathBuf),
+    /// The contents or the metadata of a file were modified, or it was
+    /// replaced.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Modified(PathBuf),
+    /// A file or directory was removed, or moved away from the path.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Removed(PathBuf),
+    /// Some changes could not be reported, so the watched paths need to be
+    /// scanned again to find out their state.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Rescan,
+}
+
+/// A directory being walked.
+struct WalkDirLevel {
+    entries: vec::IntoIter<io::Result<DirEntry>>,
+    /// The entry for this directory, to be yielded once its contents have
+    /// been, if the walk is `contents_first`.
+    entry: Option<DirEntry>,
+    /// The canonical path of this directory if symbolic links are followed,
+    /// to detect loops.
+    canonical: Option<PathBuf>,
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -308,6 +448,118 @@ impl File {
             inner: self.inner.duplicate()?
         })
     }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it
+    /// can be acquired.
+    ///
+    /// At most one exclusive lock, or any number of shared locks, may be held
+    /// on a file at once. A lock belongs to the open file itself, so it is
+    /// shared with handles created by `try_clone`, and is released by
+    /// `unlock` or once the file and all such handles are closed. Locking a
+    /// file which this handle already has locked changes the kind of lock
+    /// rather than nesting it.
+    ///
+    /// The lock is advisory: it only excludes others who also lock the file,
+    /// and does not prevent anyone from reading or writing it.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `flock` with `LOCK_EX` on Unix,
+    /// except on Solaris where `fcntl` with `F_SETLKW` is used, and to
+    /// `LockFileEx` with `LOCKFILE_EXCLUSIVE_LOCK` on Windows.
+    /// Note that, this [may change in the future][changes].
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// The `fcntl` locks of Solaris belong to the process instead of the open
+    /// file, and are released when any handle to the file is closed. The
+    /// locks of Windows are mandatory, so reads and writes of a locked file
+    /// through other handles fail, and a lock must be released with `unlock`
+    /// before another kind of lock is acquired through the same handle.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::OpenOptions;
+    /// use std::io::prelude::*;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let mut f = try!(OpenOptions::new().append(true).create(true).open("shared.log"));
+    /// try!(f.lock_exclusive());
+    /// try!(f.write_all(b"one whole line\n"));
+    /// try!(f.unlock());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock(true, true)
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of shared locks may be held on a file at once, but none
+    /// while there is an exclusive lock on it. See `lock_exclusive` for how
+    /// locks behave.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `flock` with `LOCK_SH` on Unix,
+    /// except on Solaris where `fcntl` with `F_SETLKW` is used, and to
+    /// `LockFileEx` on Windows.
+    /// Note that, this [may change in the future][changes].
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock(false, true)
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the file without
+    /// blocking.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `WouldBlock` if
+    /// another lock is held on the file. See `lock_exclusive` for how locks
+    /// behave.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::ErrorKind;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = try!(File::create("cache.lock"));
+    /// match f.try_lock() {
+    ///     Ok(()) => println!("got the lock"),
+    ///     Err(ref e) if e.kind() == ErrorKind::WouldBlock => println!("cache is busy"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock(&self) -> io::Result<()> {
+        self.inner.lock(true, false)
+    }
+
+    /// Releases the lock held on the file by `lock_exclusive`, `lock_shared`
+    /// or `try_lock`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `flock` with `LOCK_UN` on Unix,
+    /// except on Solaris where `fcntl` with `F_SETLK` is used, and to
+    /// `UnlockFileEx` on Windows.
+    /// Note that, this [may change in the future][changes].
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -1456,16 +1708,500 @@ impl AsInnerMut<fs_imp::DirBuilder> for DirBuilder {
     }
 }
 
+/// Returns an iterator over all the entries of a directory tree, recursively.
+///
+/// The iterator yields every file, directory and symbolic link below `path`,
+/// but not `path` itself, each directory before its contents. Symbolic links
+/// are not followed. Use `WalkOptions` to change any of this.
+///
+/// # Errors
+///
+/// This function will return an error if `path` can't be read as a
+/// directory; see `read_dir`. Errors reading directories within the tree
+/// are yielded by the iterator instead.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// for entry in try!(fs::walk_dir(".")) {
+///     let entry = try!(entry);
+///     if try!(entry.file_type()).is_file() {
+///         println!("{:?}", entry.path());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> io::Result<WalkDir> {
+    WalkOptions::new().walk(path)
+}
+
+impl WalkOptions {
+    /// Creates a blank new set of options ready for configuration.
+    ///
+    /// By default the whole tree is walked in the order in which the
+    /// platform lists directories, directories are yielded before their
+    /// contents and symbolic links are not followed.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn new() -> WalkOptions {
+        WalkOptions {
+            min_depth: 1,
+            max_depth: usize::MAX,
+            follow_links: false,
+            sort_by_file_name: false,
+            contents_first: false,
+        }
+    }
+
+    /// Sets the minimum depth of the entries which are yielded.
+    ///
+    /// The entries of the directory being walked have a depth of 1, their
+    /// own entries a depth of 2, and so on. Entries above the minimum depth
+    /// are still walked into, but not yielded.
+    ///
+    /// This option defaults to 1.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn min_depth(&mut self, depth: usize) -> &mut WalkOptions {
+        self.min_depth = depth; self
+    }
+
+    /// Sets the maximum depth of the entries which are yielded.
+    ///
+    /// Directories at the maximum depth are yielded but not walked into, so
+    /// a maximum depth of 1 yields the same entries as `read_dir`.
+    ///
+    /// This option defaults to no limit.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn max_depth(&mut self, depth: usize) -> &mut WalkOptions {
+        self.max_depth = depth; self
+    }
+
+    /// Sets the option for following symbolic links to directories.
+    ///
+    /// If this is `true`, links to directories are walked into as if they
+    /// were directories, and yield an error instead if they point to a
+    /// directory being walked, which would otherwise never end. The entries
+    /// yielded for the links still describe the links themselves, so use
+    /// `fs::metadata` on their path to learn about their target.
+    ///
+    /// If this is `false`, links are yielded like any other file.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn follow_links(&mut self, follow: bool) -> &mut WalkOptions {
+        self.follow_links = follow; self
+    }
+
+    /// Sets the option for sorting the entries of each directory by file
+    /// name.
+    ///
+    /// Without this the order of the entries is unspecified and may differ
+    /// between platforms and file systems.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn sort_by_file_name(&mut self, sort: bool) -> &mut WalkOptions {
+        self.sort_by_file_name = sort; self
+    }
+
+    /// Sets the option for yielding directories after their contents rather
+    /// than before.
+    ///
+    /// This is the order in which a tree must be processed to, for example,
+    /// remove it or make it read-only.
+    ///
+    /// This option defaults to `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn contents_first(&mut self, contents_first: bool) -> &mut WalkOptions {
+        self.contents_first = contents_first; self
+    }
+
+    /// Starts walking the directory tree at `path` with the options
+    /// configured in this builder.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` can't be read as a
+    /// directory; see `read_dir`.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn walk<P: AsRef<Path>>(&self, path: P) -> io::Result<WalkDir> {
+        self._walk(path.as_ref())
+    }
+
+    fn _walk(&self, path: &Path) -> io::Result<WalkDir> {
+        let canonical = if self.follow_links {
+            Some(canonicalize(path)?)
+        } else {
+            None
+        };
+        let root = WalkDirLevel {
+            entries: self.read_entries(path)?,
+            entry: None,
+            canonical: canonical,
+        };
+        Ok(WalkDir {
+            opts: self.clone(),
+            stack: vec![root],
+        })
+    }
+
+    /// Reads the entries of a directory up front, so that the walk only has
+    /// one directory open at a time however deep the tree is.
+    fn read_entries(&self, path: &Path) -> io::Result<vec::IntoIter<io::Result<DirEntry>>> {
+        let mut entries = read_dir(path)?.collect::<Vec<_>>();
+        if self.sort_by_file_name {
+            // Errors go first, and are otherwise left in order.
+            entries.sort_by(|a, b| {
+                match (a, b) {
+                    (&Ok(ref a), &Ok(ref b)) => a.file_name().cmp(&b.file_name()),
+                    (&Ok(_), &Err(_)) => cmp::Ordering::Greater,
+                    (&Err(_), &Ok(_)) => cmp::Ordering::Less,
+                    (&Err(_), &Err(_)) => cmp::Ordering::Equal,
+                }
+            });
+        }
+        Ok(entries.into_iter())
+    }
+}
+
+impl WalkDir {
+    /// Returns whether `entry` is a directory which should be walked into.
+    fn is_walked_dir(&self, entry: &DirEntry) -> bool {
+        match entry.file_type() {
+            Ok(ref ty) if ty.is_dir() => true,
+            // A broken link is yielded as is.
+            Ok(ref ty) if ty.is_symlink() && self.opts.follow_links => {
+                metadata(entry.path()).map(|m| m.is_dir()).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// Starts walking the directory `entry`. Errors are yielded as its
+    /// contents.
+    fn push(&mut self, entry: DirEntry) -> Option<DirEntry> {
+        let path = entry.path();
+        let mut level = WalkDirLevel {
+            entries: Vec::new().into_iter(),
+            entry: None,
+            canonical: None,
+        };
+        let entries = if self.opts.follow_links {
+            match canonicalize(&path) {
+                Ok(canonical) => {
+                    let is_loop = self.stack.iter().any(|ancestor| {
+                        ancestor.canonical.as_ref() == Some(&canonical)
+                    });
+                    level.canonical = Some(canonical);
+                    if is_loop {
+                        Err(io::Error::new(io::ErrorKind::Other,
+                                           "found a file system loop"))
+                    } else {
+                        self.opts.read_entries(&path)
+                    }
+                }
+                Err(e) => Err(e),
+            }
+        } else {
+            self.opts.read_entries(&path)
+        };
+        level.entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => vec![Err(e)].into_iter(),
+        };
+        let entry = if self.opts.contents_first {
+            level.entry = Some(entry);
+            None
+        } else {
+            Some(entry)
+        };
+        self.stack.push(level);
+        entry
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+impl Iterator for WalkDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        loop {
+            // The depth of the entries of the directory on top of the stack.
+            let depth = self.stack.len();
+            let next = match self.stack.last_mut() {
+                Some(level) => level.entries.next(),
+                None => return None,
+            };
+            let entry = match next {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    match self.stack.pop().and_then(|level| level.entry) {
+                        Some(entry) => {
+                            if depth > self.opts.min_depth {
+                                return Some(Ok(entry))
+                            }
+                        }
+                        None => {}
+                    }
+                    continue
+                }
+            };
+            let entry = if depth < self.opts.max_depth && self.is_walked_dir(&entry) {
+                match self.push(entry) {
+                    Some(entry) => entry,
+                    None => continue,
+                }
+            } else {
+                entry
+            };
+            if depth >= self.opts.min_depth {
+                return Some(Ok(entry))
+            }
+        }
+    }
+}
+
+/// Recursively copies the contents of a directory to a new directory.
+///
+/// `to` is created with the permissions of `from`, and every file,
+/// directory and symbolic link below `from` is copied to the same place
+/// below `to`. Files are copied with `fs::copy`, so their permission bits
+/// are copied too, and symbolic links are recreated pointing at the same
+/// path rather than followed. On success, the total number of bytes copied
+/// is returned.
+///
+/// The permissions of each directory are only set once its contents have
+/// been copied, so read-only directories are copied as well.
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `from` is not a directory, or can't be read.
+/// * `to` already exists.
+/// * `to` is inside `from`, which would copy `to` into itself.
+/// * Any file, directory or link in the tree fails to be copied.
+///
+/// A copy which fails part way leaves behind what was already copied.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_dir)]
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// try!(fs::copy_dir("assets", "target/assets"));
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_copy_dir", issue = "0")]
+pub fn copy_dir<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let parent = match to.parent() {
+        Some(parent) if parent != Path::new("") => canonicalize(parent),
+        _ => canonicalize("."),
+    };
+    if let (Ok(from), Ok(parent)) = (canonicalize(from), parent) {
+        if parent.starts_with(&from) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "cannot copy a directory into itself"))
+        }
+    }
+    copy_dir_inner(from, to)
+}
+
+impl Watcher {
+    /// Creates a new watcher, which is not watching anything yet, and the
+    /// receiver of its events.
+    ///
+    /// Changes are held back for `delay` after the first change to a path,
+    /// to be coalesced with the changes which follow it.
+    ///
+    /// The receiver yields an error if the watcher fails to find out about
+    /// changes, after which it stops.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn new(delay: Duration) -> io::Result<(Watcher, Receiver<io::Result<WatchEvent>>)> {
+        let (tx, rx) = mpsc::channel();
+        let watcher = fs_imp::Watcher::new(delay, tx)?;
+        Ok((Watcher(watcher), rx))
+    }
+
+    /// Starts watching `path` for changes.
+    ///
+    /// If `path` is a file, changes to the file itself are reported. If it
+    /// is a directory, changes to its entries are reported too, and if
+    /// `recursive` is `true`, changes to everything below it, including
+    /// the directories which are created later. Symbolic links are not
+    /// followed below `path`.
+    ///
+    /// Watching a path again replaces its watch.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not exist, or if
+    /// the limit on the number of watches of the platform is reached.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn watch<P: AsRef<Path>>(&self, path: P, recursive: bool) -> io::Result<()> {
+        self.0.watch(path.as_ref(), recursive)
+    }
+
+    /// Stops watching a path which was given to `watch`.
+    ///
+    /// Changes which were already seen may still be reported.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidInput` if
+    /// `path` is not being watched.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn unwatch<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.0.unwatch(path.as_ref())
+    }
+}
+
+#[unstable(feature = "fs_watcher", issue = "0")]
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Watcher { .. }")
+    }
+}
+
+/// Atomically replaces the contents of a file with `contents`, creating it if
+/// it does not exist.
+///
+/// The contents are written to a new temporary file in the same directory,
+/// which is synced to disk and then renamed over `path`. Readers of `path`
+/// therefore see either the old contents or the new, never a partial write,
+/// and a crash leaves one or the other in place. The directory is then
+/// synced too, so that the rename itself is durable once this function
+/// returns.
+///
+/// If `path` already exists, its permissions are given to the new file.
+/// Any other metadata of the old file, such as its owner or its hard links,
+/// is not kept.
+///
+/// # Platform-specific behavior
+///
+/// The directory is synced by opening it and calling `fsync` on Unix. This
+/// is not possible on Windows, where `MoveFileEx` is used for the rename.
+/// Note that, this [may change in the future][changes].
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `path` has no file name.
+/// * The user lacks permission to create files in the directory of `path`.
+/// * Writing or syncing the temporary file fails.
+///
+/// The temporary file is removed if anything fails before it is renamed.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_write_atomic)]
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// try!(fs::write_atomic("settings.toml", b"verbose = true\n"));
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_write_atomic", issue = "0")]
+pub fn write_atomic<P: AsRef<Path>>(path: P, contents: &[u8]) -> io::Result<()> {
+    let path = path.as_ref();
+    let file_name = match path.file_name() {
+        Some(name) => name,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "path has no file name")),
+    };
+    let dir = match path.parent() {
+        Some(parent) if parent != Path::new("") => parent,
+        _ => Path::new("."),
+    };
+
+    let tmp = write_temp(dir, file_name, contents)?;
+    let result = match metadata(path) {
+        Ok(m) => set_permissions(&tmp, m.permissions()),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result.and_then(|()| rename(&tmp, path)) {
+        let _ = remove_file(&tmp);
+        return Err(e)
+    }
+    fs_imp::sync_dir(dir)
+}
+
+/// Writes `contents` to a new file with a random name derived from
+/// `file_name` in `dir`, and syncs it to disk.
+fn write_temp(dir: &Path, file_name: &OsStr, contents: &[u8]) -> io::Result<PathBuf> {
+    let mut tries = 0;
+    loop {
+        let mut name = OsString::from(".");
+        name.push(file_name);
+        name.push(format!(".{:08x}.tmp", rand::thread_rng().next_u32()));
+        let tmp = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&tmp) {
+            Ok(mut file) => {
+                let result = file.write_all(contents).and_then(|()| file.sync_all());
+                if let Err(e) = result {
+                    drop(file);
+                    let _ = remove_file(&tmp);
+                    return Err(e)
+                }
+                return Ok(tmp)
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists && tries < 16 => {}
+            Err(e) => return Err(e),
+        }
+        tries += 1;
+    }
+}
+
+fn copy_dir_inner(from: &Path, to: &Path) -> io::Result<u64> {
+    let perm = metadata(from)?.permissions();
+    create_dir(to)?;
+    let mut copied = 0;
+    for entry in read_dir(from)? {
+        let entry = entry?;
+        let (from, to) = (entry.path(), to.join(entry.file_name()));
+        let ty = entry.file_type()?;
+        if ty.is_dir() {
+            copied += copy_dir_inner(&from, &to)?;
+        } else if ty.is_symlink() {
+            fs_imp::copy_symlink(&from, &to)?;
+        } else {
+            copied += copy(&from, &to)?;
+        }
+    }
+    set_permissions(to, perm)?;
+    Ok(copied)
+}
+
 #[cfg(test)]
 mod tests {
     use prelude::v1::*;
     use io::prelude::*;
 
     use fs::{self, File, OpenOptions};
-    use io::{ErrorKind, SeekFrom};
+    use io::{self, ErrorKind, SeekFrom};
     use path::Path;
     use rand::{StdRng, Rng};
     use str;
+    use sync::mpsc::Receiver;
+    use thread;
+    use time::{Duration, Instant};
     use sys_common::io::test::{TempDir, tmpdir};
 
     #[cfg(windows)]
@@ -1983,6 +2719,277 @@ mod tests {
         check!(fs::set_permissions(&out, attr.permissions()));
     }
 
+    #[test]
+    fn file_lock() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(OpenOptions::new().write(true).open(&path));
+
+        check!(f1.lock_exclusive());
+        assert_eq!(f2.try_lock().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f1.unlock());
+        check!(f2.try_lock());
+        assert_eq!(f1.try_lock().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f2.unlock());
+
+        check!(f1.lock_shared());
+        check!(f2.lock_shared());
+        assert_eq!(check!(File::create(&path)).try_lock().unwrap_err().kind(),
+                   ErrorKind::WouldBlock);
+        check!(f1.unlock());
+        check!(f2.unlock());
+
+        // Closing the file releases its lock.
+        check!(f1.lock_exclusive());
+        drop(f1);
+        check!(f2.try_lock());
+    }
+
+    #[test]
+    fn write_atomic() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("atomic");
+        check!(fs::write_atomic(&path, b"hello"));
+        let mut contents = String::new();
+        check!(check!(File::open(&path)).read_to_string(&mut contents));
+        assert_eq!(contents, "hello");
+
+        // Windows refuses to replace read-only files.
+        let mut p = check!(path.metadata()).permissions();
+        p.set_readonly(cfg!(unix));
+        check!(fs::set_permissions(&path, p));
+        check!(fs::write_atomic(&path, b"world"));
+        contents.truncate(0);
+        check!(check!(File::open(&path)).read_to_string(&mut contents));
+        assert_eq!(contents, "world");
+        let mut p = check!(path.metadata()).permissions();
+        assert_eq!(p.readonly(), cfg!(unix));
+        p.set_readonly(false);
+        check!(fs::set_permissions(&path, p));
+
+        // Only the file itself is left behind.
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+
+        assert_eq!(fs::write_atomic(tmpdir.join("missing/file"), b"").unwrap_err().kind(),
+                   ErrorKind::NotFound);
+        assert_eq!(fs::write_atomic("/", b"").unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn mmap() {
+        use os::unix::fs::{Advice, FileExt};
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("mmap");
+        let contents: Vec<u8> = (0..20000).map(|i| i as u8).collect();
+        check!(check!(File::create(&path)).write_all(&contents));
+
+        let file = check!(File::open(&path));
+        let map = check!(unsafe { file.map() });
+        assert!(&map[..] == &contents[..]);
+        check!(map.advise(Advice::Sequential));
+        check!(map.advise_range(Advice::WillNeed, 5000, 100));
+        assert_eq!(map.advise_range(Advice::Random, 19000, 2000).unwrap_err().kind(),
+                   ErrorKind::InvalidInput);
+        // The map outlives the file.
+        drop(file);
+        assert_eq!(map[19999], 19999 as u8);
+
+        // Ranges don't need to be aligned to pages.
+        let file = check!(File::open(&path));
+        let map = check!(unsafe { file.map_range(5001, 3) });
+        assert_eq!(&map[..], &contents[5001..5004]);
+        // Writable maps need the file to be writable.
+        assert!(unsafe { file.map_mut() }.is_err());
+
+        let file = check!(OpenOptions::new().read(true).write(true).open(&path));
+        let mut map = check!(unsafe { file.map_range_mut(10000, 10000) });
+        for byte in map.iter_mut() {
+            *byte = 0;
+        }
+        check!(map.flush_range(0, 100));
+        check!(map.flush());
+        drop(map);
+        let mut read = Vec::new();
+        check!(check!(File::open(&path)).read_to_end(&mut read));
+        assert!(&read[..10000] == &contents[..10000]);
+        assert!(read[10000..].iter().all(|&b| b == 0));
+
+        let empty = check!(File::create(tmpdir.join("empty")));
+        assert_eq!(check!(unsafe { empty.map() }).len(), 0);
+    }
+
+    /// Receives events until `expected` is among them, failing after a
+    /// few seconds.
+    fn wait_for(rx: &Receiver<io::Result<fs::WatchEvent>>, expected: fs::WatchEvent)
+                -> Vec<fs::WatchEvent> {
+        let start = Instant::now();
+        let mut events = Vec::new();
+        while start.elapsed() < Duration::from_secs(10) {
+            match rx.try_recv() {
+                Ok(event) => {
+                    let event = check!(event);
+                    let done = event == expected;
+                    events.push(event);
+                    if done {
+                        return events
+                    }
+                }
+                Err(_) => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        panic!("no {:?} in {:?}", expected, events);
+    }
+
+    #[test]
+    fn watcher() {
+        use fs::WatchEvent::*;
+
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("watched");
+        check!(fs::create_dir(&root));
+        let (watcher, rx) = check!(fs::Watcher::new(Duration::from_millis(50)));
+        check!(watcher.watch(&root, true));
+
+        // The file may be created before its directory is watched, and it is
+        // still reported.
+        check!(fs::create_dir(root.join("sub")));
+        check!(check!(File::create(root.join("sub/file"))).write_all(b"hello"));
+        let events = wait_for(&rx, Created(root.join("sub/file")));
+        assert!(events.contains(&Created(root.join("sub"))));
+
+        check!(check!(OpenOptions::new().append(true).open(root.join("sub/file")))
+                   .write_all(b" world"));
+        wait_for(&rx, Modified(root.join("sub/file")));
+
+        check!(fs::remove_file(root.join("sub/file")));
+        wait_for(&rx, Removed(root.join("sub/file")));
+
+        check!(watcher.unwatch(&root));
+        assert_eq!(watcher.unwatch(&root).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert!(watcher.watch(tmpdir.join("missing"), false).is_err());
+
+        // The channel is closed once the watcher is gone.
+        drop(watcher);
+        for event in rx.iter() {
+            check!(event);
+        }
+    }
+
+    fn walked(walk: fs::WalkDir, root: &Path) -> Vec<String> {
+        walk.map(|entry| {
+            let path = check!(entry).path();
+            let path = path.strip_prefix(root).unwrap();
+            path.to_str().unwrap().replace("\\", "/")
+        }).collect()
+    }
+
+    #[test]
+    fn walk_dir() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        check!(fs::create_dir_all(root.join("b/c")));
+        check!(fs::create_dir_all(root.join("a")));
+        check!(File::create(root.join("a/1")));
+        check!(File::create(root.join("b/c/2")));
+        check!(File::create(root.join("3")));
+
+        let mut unsorted = walked(check!(fs::walk_dir(&root)), &root);
+        unsorted.sort();
+        assert_eq!(unsorted, ["3", "a", "a/1", "b", "b/c", "b/c/2"]);
+
+        let mut opts = fs::WalkOptions::new();
+        opts.sort_by_file_name(true);
+        assert_eq!(walked(check!(opts.walk(&root)), &root),
+                   ["3", "a", "a/1", "b", "b/c", "b/c/2"]);
+        opts.contents_first(true);
+        assert_eq!(walked(check!(opts.walk(&root)), &root),
+                   ["3", "a/1", "a", "b/c/2", "b/c", "b"]);
+        opts.contents_first(false).min_depth(2).max_depth(2);
+        assert_eq!(walked(check!(opts.walk(&root)), &root), ["a/1", "b/c"]);
+
+        assert!(fs::walk_dir(root.join("3")).is_err());
+    }
+
+    #[test]
+    fn walk_dir_follow_links() {
+        let tmpdir = tmpdir();
+        if !got_symlink_permission(&tmpdir) { return };
+        let root = tmpdir.join("root");
+        check!(fs::create_dir_all(root.join("a")));
+        check!(File::create(root.join("a/1")));
+        check!(symlink_dir(root.join("a"), root.join("b")));
+        check!(symlink_dir(&root, root.join("a/loop")));
+
+        let mut opts = fs::WalkOptions::new();
+        opts.sort_by_file_name(true);
+        assert_eq!(walked(check!(opts.walk(&root)), &root), ["a", "a/1", "a/loop", "b"]);
+
+        opts.follow_links(true);
+        let mut found = Vec::new();
+        let mut errors = 0;
+        for entry in check!(opts.walk(&root)) {
+            match entry {
+                Ok(entry) => found.push(entry.path()),
+                Err(_) => errors += 1,
+            }
+        }
+        // The link back to the root is yielded but not walked, under both
+        // `a` and `b`.
+        assert_eq!(errors, 2);
+        assert_eq!(found, [root.join("a"), root.join("a/1"), root.join("a/loop"),
+                           root.join("b"), root.join("b/1"), root.join("b/loop")]);
+    }
+
+    #[test]
+    fn copy_dir() {
+        let tmpdir = tmpdir();
+        let from = tmpdir.join("from");
+        let to = tmpdir.join("to");
+        check!(fs::create_dir_all(from.join("a/b")));
+        check!(check!(File::create(from.join("a/1"))).write(b"hello"));
+        check!(check!(File::create(from.join("a/b/2"))).write(b"world!"));
+
+        let readonly = from.join("a/b");
+        let mut p = check!(readonly.metadata()).permissions();
+        p.set_readonly(true);
+        check!(fs::set_permissions(&readonly, p));
+
+        assert_eq!(check!(fs::copy_dir(&from, &to)), 11);
+        let mut contents = String::new();
+        check!(check!(File::open(to.join("a/b/2"))).read_to_string(&mut contents));
+        assert_eq!(contents, "world!");
+        assert!(check!(to.join("a/b").metadata()).permissions().readonly());
+        assert!(!check!(to.join("a").metadata()).permissions().readonly());
+
+        error!(fs::copy_dir(&from, &to), "exists");
+        assert_eq!(fs::copy_dir(&from, from.join("a/into")).unwrap_err().kind(),
+                   ErrorKind::InvalidInput);
+
+        for dir in &[&from, &to] {
+            let mut p = check!(dir.join("a/b").metadata()).permissions();
+            p.set_readonly(false);
+            check!(fs::set_permissions(dir.join("a/b"), p));
+        }
+    }
+
+    #[test]
+    fn copy_dir_symlinks() {
+        let tmpdir = tmpdir();
+        if !got_symlink_permission(&tmpdir) { return };
+        let from = tmpdir.join("from");
+        let to = tmpdir.join("to");
+        check!(fs::create_dir_all(&from));
+        check!(check!(File::create(from.join("file"))).write(b"hello"));
+        check!(symlink_file("file", from.join("link")));
+
+        assert_eq!(check!(fs::copy_dir(&from, &to)), 5);
+        assert!(check!(fs::symlink_metadata(to.join("link"))).file_type().is_symlink());
+        assert_eq!(check!(fs::read_link(to.join("link"))), Path::new("file"));
+    }
+
     #[cfg(windows)]
     #[test]
     fn copy_file_preserves_streams() {
@@ -27,12 +27,16 @@ pub use self::tcp::{TcpStream, TcpListener, Incoming};
 pub use self::udp::UdpSocket;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::parser::AddrParseError;
+#[cfg(unix)]
+#[unstable(feature = "net_poll", issue = "0")]
+pub use self::poll::{Event, Events, EventsIter, Interest, Poll, Token};
 
 mod ip;
 mod addr;
 mod tcp;
 mod udp;
 mod parser;
+#[cfg(unix)] mod poll;
 #[cfg(test)] mod test;
 
 /// Possible values which can be passed to the `shutdown` method of `TcpStream`.
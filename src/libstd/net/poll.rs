@@ -0,0 +1,380 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use prelude::v1::*;
+
+use fmt;
+use io;
+use ops::BitOr;
+use os::unix::io::AsRawFd;
+use slice;
+use sys::poll as poll_imp;
+use time::Duration;
+
+/// A registry of sockets which can wait until any of them is ready for I/O.
+///
+/// Sockets are registered with a `Token`, which identifies them in the
+/// events returned by `poll`, and an `Interest` in reading, writing or both.
+/// Any socket which implements `AsRawFd` can be registered, such as
+/// `TcpStream`, `TcpListener`, `UdpSocket` and the Unix domain sockets of
+/// `std::os::unix::net`. They should usually be put into nonblocking mode
+/// with `set_nonblocking`, so that handling an event cannot block.
+///
+/// Readiness is level-triggered: as long as a socket is ready, every call to
+/// `poll` reports it again.
+///
+/// On Linux this is implemented with epoll; on other Unix platforms it uses
+/// `poll(2)`. It is not available on Windows.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(net_poll)]
+///
+/// use std::io::Read;
+/// use std::net::{Events, Interest, Poll, TcpStream, Token};
+///
+/// let mut streams = vec![TcpStream::connect("127.0.0.1:8080").unwrap(),
+///                        TcpStream::connect("127.0.0.1:8081").unwrap()];
+/// let poll = Poll::new().unwrap();
+/// for (i, stream) in streams.iter().enumerate() {
+///     stream.set_nonblocking(true).unwrap();
+///     poll.register(stream, Token(i), Interest::readable()).unwrap();
+/// }
+///
+/// let mut events = Events::with_capacity(16);
+/// let mut buf = [0; 1024];
+/// loop {
+///     poll.poll(&mut events, None).unwrap();
+///     for event in &events {
+///         let n = streams[event.token().0].read(&mut buf).unwrap();
+///         println!("read {} bytes from stream {}", n, event.token().0);
+///     }
+/// }
+/// ```
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct Poll(poll_imp::Poll);
+
+/// Identifies a registered socket in the events returned by `Poll::poll`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct Token(pub usize);
+
+/// The kinds of readiness a socket is registered for.
+///
+/// Interests can be combined with `|`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct Interest {
+    readable: bool,
+    writable: bool,
+}
+
+/// A readiness event returned by `Poll::poll`.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct Event(poll_imp::Event);
+
+/// A buffer for the events returned by `Poll::poll`.
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct Events {
+    events: Vec<poll_imp::Event>,
+    capacity: usize,
+}
+
+/// An iterator over the events in an `Events` buffer.
+#[derive(Debug)]
+#[unstable(feature = "net_poll", issue = "0")]
+pub struct EventsIter<'a>(slice::Iter<'a, poll_imp::Event>);
+
+impl Poll {
+    /// Creates a new registry with no sockets.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn new() -> io::Result<Poll> {
+        poll_imp::Poll::new().map(Poll)
+    }
+
+    /// Registers `socket` for the readiness given by `interest`.
+    ///
+    /// Registering a socket twice is an error; use `reregister` to change the
+    /// token or interest of a registered socket.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn register<S: AsRawFd + ?Sized>(&self, socket: &S, token: Token, interest: Interest)
+                                         -> io::Result<()> {
+        self.0.register(socket.as_raw_fd(), token.0, interest.readable, interest.writable)
+    }
+
+    /// Changes the token and interest of a registered socket.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn reregister<S: AsRawFd + ?Sized>(&self, socket: &S, token: Token, interest: Interest)
+                                           -> io::Result<()> {
+        self.0.reregister(socket.as_raw_fd(), token.0, interest.readable, interest.writable)
+    }
+
+    /// Removes a socket from the registry.
+    ///
+    /// Sockets should be deregistered before they are closed, as their file
+    /// descriptor may be reused by a socket which is not registered.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn deregister<S: AsRawFd + ?Sized>(&self, socket: &S) -> io::Result<()> {
+        self.0.deregister(socket.as_raw_fd())
+    }
+
+    /// Waits until at least one registered socket is ready, or until
+    /// `timeout` has elapsed if it is not `None`, and replaces the contents
+    /// of `events` with the readiness events, returning their number.
+    ///
+    /// At most `events.capacity()` events are returned at once; further
+    /// events are returned by the next call.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn poll(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
+        events.events.clear();
+        self.0.wait(&mut events.events, events.capacity, timeout)?;
+        Ok(events.events.len())
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl fmt::Debug for Poll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Poll").finish()
+    }
+}
+
+impl Interest {
+    /// Interest in the socket becoming readable, or a listener having a
+    /// connection to accept.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn readable() -> Interest {
+        Interest { readable: true, writable: false }
+    }
+
+    /// Interest in the socket becoming writable.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn writable() -> Interest {
+        Interest { readable: false, writable: true }
+    }
+
+    /// Whether this includes interest in the socket becoming readable.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_readable(&self) -> bool {
+        self.readable
+    }
+
+    /// Whether this includes interest in the socket becoming writable.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl BitOr for Interest {
+    type Output = Interest;
+
+    fn bitor(self, other: Interest) -> Interest {
+        Interest {
+            readable: self.readable || other.readable,
+            writable: self.writable || other.writable,
+        }
+    }
+}
+
+impl Event {
+    /// The token the socket was registered with.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn token(&self) -> Token {
+        Token(self.0.token)
+    }
+
+    /// Whether the socket can be read from (or a connection accepted)
+    /// without blocking.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_readable(&self) -> bool {
+        self.0.readable
+    }
+
+    /// Whether the socket can be written to without blocking.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_writable(&self) -> bool {
+        self.0.writable
+    }
+
+    /// Whether the peer hung up. This is reported even without interest in
+    /// it; remaining data can still be read.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_hangup(&self) -> bool {
+        self.0.hangup
+    }
+
+    /// Whether an error is pending on the socket. This is reported even
+    /// without interest in it; `take_error` retrieves the error.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_error(&self) -> bool {
+        self.0.error
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl fmt::Debug for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Event")
+         .field("token", &self.token())
+         .field("readable", &self.0.readable)
+         .field("writable", &self.0.writable)
+         .field("hangup", &self.0.hangup)
+         .field("error", &self.0.error)
+         .finish()
+    }
+}
+
+impl Events {
+    /// Creates a buffer for at most `capacity` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn with_capacity(capacity: usize) -> Events {
+        assert!(capacity > 0, "an event buffer needs room for at least one event");
+        Events { events: Vec::with_capacity(capacity), capacity: capacity }
+    }
+
+    /// The maximum number of events returned by one call to `Poll::poll`.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of events returned by the last call to `Poll::poll`.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the last call to `Poll::poll` returned no events.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns an iterator over the events returned by the last call to
+    /// `Poll::poll`.
+    #[unstable(feature = "net_poll", issue = "0")]
+    pub fn iter(&self) -> EventsIter {
+        EventsIter(self.events.iter())
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl fmt::Debug for Events {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl<'a> IntoIterator for &'a Events {
+    type Item = Event;
+    type IntoIter = EventsIter<'a>;
+
+    fn into_iter(self) -> EventsIter<'a> {
+        self.iter()
+    }
+}
+
+#[unstable(feature = "net_poll", issue = "0")]
+impl<'a> Iterator for EventsIter<'a> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.0.next().map(|event| Event(*event))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::v1::*;
+
+    use io::{Read, Write};
+    use net::*;
+    use net::test::next_test_ip4;
+    use time::{Duration, Instant};
+
+    macro_rules! t {
+        ($e:expr) => {
+            match $e {
+                Ok(t) => t,
+                Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+            }
+        }
+    }
+
+    #[test]
+    fn timeout() {
+        let listener = t!(TcpListener::bind(next_test_ip4()));
+        let poll = t!(Poll::new());
+        t!(poll.register(&listener, Token(0), Interest::readable()));
+
+        let mut events = Events::with_capacity(4);
+        let start = Instant::now();
+        assert_eq!(t!(poll.poll(&mut events, Some(Duration::from_millis(100)))), 0);
+        assert!(events.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn accept_and_read() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+        t!(listener.set_nonblocking(true));
+        let poll = t!(Poll::new());
+        t!(poll.register(&listener, Token(0), Interest::readable()));
+
+        let mut client = t!(TcpStream::connect(&addr));
+        let mut events = Events::with_capacity(4);
+        assert_eq!(t!(poll.poll(&mut events, None)), 1);
+        let event = events.iter().next().unwrap();
+        assert_eq!(event.token(), Token(0));
+        assert!(event.is_readable() && !event.is_writable());
+
+        let (mut server, _) = t!(listener.accept());
+        t!(server.set_nonblocking(true));
+        t!(poll.register(&server, Token(1), Interest::readable() | Interest::writable()));
+        t!(poll.deregister(&listener));
+
+        // The accepted stream is writable straight away, and readable once
+        // the client has written.
+        t!(client.write_all(b"hello"));
+        let start = Instant::now();
+        loop {
+            t!(poll.poll(&mut events, Some(Duration::from_secs(10))));
+            let event = events.iter().next().unwrap();
+            assert_eq!(event.token(), Token(1));
+            assert!(event.is_writable());
+            if event.is_readable() {
+                break
+            }
+            assert!(start.elapsed() < Duration::from_secs(10));
+        }
+        let mut buf = [0; 5];
+        t!(server.read_exact(&mut buf));
+        assert_eq!(&buf, b"hello");
+
+        // With all data read, there is nothing left to wait for.
+        t!(poll.reregister(&server, Token(2), Interest::readable()));
+        assert_eq!(t!(poll.poll(&mut events, Some(Duration::from_millis(10)))), 0);
+        assert!(poll.register(&server, Token(3), Interest::readable()).is_err());
+    }
+}
@@ -12,7 +12,13 @@
 
 use libc;
 
+use ffi::{OsStr, OsString};
+use fmt;
 use fs::Metadata;
+use io;
+use os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+use path::Path;
+use sys::inotify;
 use sys_common::AsInner;
 
 #[allow(deprecated)]
@@ -126,3 +132,235 @@ impl MetadataExt for Metadata {
         self.as_inner().as_inner().st_blocks as u64
     }
 }
+
+/// The file was accessed, for example read.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ACCESS: u32 = inotify::IN_ACCESS;
+
+/// The file was modified, for example written to.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_MODIFY: u32 = inotify::IN_MODIFY;
+
+/// The metadata of the file changed, for example its permissions,
+/// timestamps or link count.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ATTRIB: u32 = inotify::IN_ATTRIB;
+
+/// The file was closed after being opened for writing.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_CLOSE_WRITE: u32 = inotify::IN_CLOSE_WRITE;
+
+/// The file was closed after being opened only for reading.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_CLOSE_NOWRITE: u32 = inotify::IN_CLOSE_NOWRITE;
+
+/// The file was opened.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_OPEN: u32 = inotify::IN_OPEN;
+
+/// A file was moved out of the watched directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_MOVED_FROM: u32 = inotify::IN_MOVED_FROM;
+
+/// A file was moved into the watched directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_MOVED_TO: u32 = inotify::IN_MOVED_TO;
+
+/// A file was created in the watched directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_CREATE: u32 = inotify::IN_CREATE;
+
+/// A file was removed from the watched directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_DELETE: u32 = inotify::IN_DELETE;
+
+/// The watched file or directory itself was removed.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_DELETE_SELF: u32 = inotify::IN_DELETE_SELF;
+
+/// The watched file or directory itself was moved.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_MOVE_SELF: u32 = inotify::IN_MOVE_SELF;
+
+/// All of the events above.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ALL_EVENTS: u32 = inotify::IN_ALL_EVENTS;
+
+/// The file system holding the watched file was unmounted.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_UNMOUNT: u32 = inotify::IN_UNMOUNT;
+
+/// Events were dropped because the queue of the inotify instance
+/// overflowed. The watch descriptor of this event is -1.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_Q_OVERFLOW: u32 = inotify::IN_Q_OVERFLOW;
+
+/// The watch was removed, explicitly or because its file is gone.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_IGNORED: u32 = inotify::IN_IGNORED;
+
+/// Only watch the path if it is a directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ONLYDIR: u32 = inotify::IN_ONLYDIR;
+
+/// Don't follow the path if it is a symbolic link.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_DONT_FOLLOW: u32 = inotify::IN_DONT_FOLLOW;
+
+/// Don't report events for the entries of a watched directory once
+/// they have been removed from it.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_EXCL_UNLINK: u32 = inotify::IN_EXCL_UNLINK;
+
+/// Add the events to the mask of an existing watch of the path
+/// instead of replacing it.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_MASK_ADD: u32 = inotify::IN_MASK_ADD;
+
+/// The subject of the event is a directory.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ISDIR: u32 = inotify::IN_ISDIR;
+
+/// Remove the watch after its first event.
+#[unstable(feature = "inotify", issue = "0")]
+pub const IN_ONESHOT: u32 = inotify::IN_ONESHOT;
+
+/// An inotify instance, which reports changes to the files it watches.
+///
+/// This is a thin wrapper around the `inotify(7)` API of Linux: paths are
+/// watched for the events of a mask, and reading from the instance yields
+/// the events which have happened since it was last read. For a watcher
+/// which is portable, handles whole trees and coalesces events, see
+/// `fs::Watcher`, which is implemented with inotify on Linux.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(inotify)]
+/// use std::os::linux::fs::{Inotify, IN_CLOSE_WRITE};
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let inotify = try!(Inotify::new());
+/// try!(inotify.add_watch("src", IN_CLOSE_WRITE));
+/// loop {
+///     for event in try!(inotify.read_events()) {
+///         println!("{:?} was written", event.name());
+///     }
+/// }
+/// # }
+/// ```
+#[unstable(feature = "inotify", issue = "0")]
+pub struct Inotify(inotify::Inotify);
+
+/// Identifies a watch of an `Inotify` instance.
+///
+/// Watching a path which is already watched returns the descriptor of the
+/// existing watch.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[unstable(feature = "inotify", issue = "0")]
+pub struct WatchDescriptor(libc::c_int);
+
+/// An event read from an `Inotify` instance.
+#[derive(Debug)]
+#[unstable(feature = "inotify", issue = "0")]
+pub struct InotifyEvent(inotify::Event);
+
+impl Inotify {
+    /// Creates a new inotify instance, without any watches.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn new() -> io::Result<Inotify> {
+        inotify::Inotify::new().map(Inotify)
+    }
+
+    /// Starts watching `path` for the events in `mask`.
+    ///
+    /// If `path` is a directory, the events of its entries are reported
+    /// too, with their names. The directories below it are not watched.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn add_watch<P: AsRef<Path>>(&self, path: P, mask: u32) -> io::Result<WatchDescriptor> {
+        self.0.add_watch(path.as_ref(), mask).map(WatchDescriptor)
+    }
+
+    /// Stops watching the path of a watch descriptor.
+    ///
+    /// An event with `IN_IGNORED` is reported for the watch once it is gone.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn rm_watch(&self, wd: WatchDescriptor) -> io::Result<()> {
+        self.0.rm_watch(wd.0)
+    }
+
+    /// Reads the events which have happened, blocking until there is at
+    /// least one unless the instance is in non-blocking mode.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn read_events(&self) -> io::Result<Vec<InotifyEvent>> {
+        let mut events = Vec::new();
+        self.0.read_events(&mut events)?;
+        Ok(events.into_iter().map(InotifyEvent).collect())
+    }
+
+    /// Moves this instance into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, `read_events` returns an error of the kind
+    /// `WouldBlock` instead of waiting for an event.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking);
+        Ok(())
+    }
+}
+
+#[unstable(feature = "inotify", issue = "0")]
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.fd().raw()
+    }
+}
+
+#[unstable(feature = "inotify", issue = "0")]
+impl IntoRawFd for Inotify {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_fd().into_raw()
+    }
+}
+
+#[unstable(feature = "inotify", issue = "0")]
+impl fmt::Debug for Inotify {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Inotify").field("fd", &self.0.fd().raw()).finish()
+    }
+}
+
+impl InotifyEvent {
+    /// Returns the watch descriptor of the watch which reported the event.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn wd(&self) -> WatchDescriptor {
+        WatchDescriptor(self.0.wd)
+    }
+
+    /// Returns the mask of the events which happened, along with flags
+    /// such as `IN_ISDIR`.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn mask(&self) -> u32 {
+        self.0.mask
+    }
+
+    /// Returns the value which connects the `IN_MOVED_FROM` and
+    /// `IN_MOVED_TO` events of a rename, or 0 for other events.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn cookie(&self) -> u32 {
+        self.0.cookie
+    }
+
+    /// Returns the name of the entry of the watched directory which the
+    /// event is about, or `None` if it is about the watched path itself.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn name(&self) -> Option<&OsStr> {
+        self.0.name.as_ref().map(|name| name.as_os_str())
+    }
+
+    /// Consumes the event, returning its name.
+    #[unstable(feature = "inotify", issue = "0")]
+    pub fn into_name(self) -> Option<OsString> {
+        self.0.name
+    }
+}
@@ -17,12 +17,14 @@ use io::prelude::*;
 
 use ffi::OsStr;
 use fmt;
+use fs;
 use io;
 use path::Path;
 use str;
-use sys::pipe::{read2, AnonPipe};
+use sys::pipe::{communicate, read2, AnonPipe};
 use sys::process as imp;
 use sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use time::Duration;
 
 /// Representation of a running or exited child process.
 ///
@@ -577,6 +579,71 @@ impl FromInner<imp::Stdio> for Stdio {
     }
 }
 
+/// Connects the stdout or stderr of a child to the stdin of another child.
+///
+/// A `ChildStdin` is the write end of a pipe, so this lets a child which is
+/// spawned later write to the stdin of one which is already running.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_stdio_from)]
+/// use std::process::{Command, Stdio};
+///
+/// let mut sort = Command::new("sort").stdin(Stdio::piped()).spawn().unwrap();
+/// let mut ls = Command::new("ls")
+///                      .stdout(Stdio::from(sort.stdin.take().unwrap()))
+///                      .spawn()
+///                      .unwrap();
+/// ls.wait().unwrap();
+/// sort.wait().unwrap();
+/// ```
+#[unstable(feature = "process_stdio_from", issue = "0")]
+impl From<ChildStdin> for Stdio {
+    fn from(child: ChildStdin) -> Stdio {
+        Stdio::from_inner(child.into_inner().into())
+    }
+}
+
+/// Connects the stdout of a child to another child, as in a shell pipeline.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_stdio_from)]
+/// use std::process::{Command, Stdio};
+///
+/// let mut ls = Command::new("ls").stdout(Stdio::piped()).spawn().unwrap();
+/// let sort = Command::new("sort")
+///                    .stdin(Stdio::from(ls.stdout.take().unwrap()))
+///                    .output()
+///                    .unwrap();
+/// ls.wait().unwrap();
+/// println!("{}", String::from_utf8_lossy(&sort.stdout));
+/// ```
+#[unstable(feature = "process_stdio_from", issue = "0")]
+impl From<ChildStdout> for Stdio {
+    fn from(child: ChildStdout) -> Stdio {
+        Stdio::from_inner(child.into_inner().into())
+    }
+}
+
+/// Connects the stderr of a child to another child.
+#[unstable(feature = "process_stdio_from", issue = "0")]
+impl From<ChildStderr> for Stdio {
+    fn from(child: ChildStderr) -> Stdio {
+        Stdio::from_inner(child.into_inner().into())
+    }
+}
+
+/// Redirects a stdio stream of a child to or from a file.
+#[unstable(feature = "process_stdio_from", issue = "0")]
+impl From<fs::File> for Stdio {
+    fn from(file: fs::File) -> Stdio {
+        Stdio::from_inner(file.into_inner().into())
+    }
+}
+
 /// Describes the result of a process after it has terminated.
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[stable(feature = "process", since = "1.0.0")]
@@ -692,6 +759,65 @@ impl Child {
         self.handle.wait().map(ExitStatus)
     }
 
+    /// Returns the exit status of the child if it has exited, without
+    /// blocking. This function will continue to return the same status
+    /// after it has returned one once.
+    ///
+    /// Unlike `wait`, this does not close the stdin handle of the child.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_try_wait)]
+    /// use std::process::Command;
+    ///
+    /// let mut child = Command::new("ls").spawn().unwrap();
+    /// match child.try_wait() {
+    ///     Ok(Some(status)) => println!("exited with: {}", status),
+    ///     Ok(None) => println!("still running"),
+    ///     Err(e) => println!("error attempting to wait: {}", e),
+    /// }
+    /// ```
+    #[unstable(feature = "process_try_wait", issue = "0")]
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        Ok(self.handle.try_wait()?.map(ExitStatus))
+    }
+
+    /// Waits for at most `dur` for the child to exit, returning its exit
+    /// status if it did, or `None` if the timeout elapsed first.
+    ///
+    /// Unlike `wait`, this does not close the stdin handle of the child.
+    ///
+    /// # Signal handling
+    ///
+    /// On Unix, the first call installs a process-wide `SIGCHLD` handler
+    /// (which calls any handler installed before it) and starts a helper
+    /// thread to be woken up when children exit. From then on, every child
+    /// exiting interrupts a thread of the process: the handler is installed
+    /// with `SA_RESTART`, but system calls which are never restarted, such
+    /// as `poll`, `select` or `nanosleep`, fail with `EINTR` in whichever
+    /// thread the signal is delivered to. Code which can't deal with `EINTR`
+    /// should block `SIGCHLD` in its threads. If `SIGCHLD` is ignored, or
+    /// the handler is replaced later on, the child is polled for instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_try_wait)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    /// if child.wait_timeout(Duration::from_secs(1)).unwrap().is_none() {
+    ///     child.kill().unwrap();
+    ///     child.wait().unwrap();
+    /// }
+    /// ```
+    #[unstable(feature = "process_try_wait", issue = "0")]
+    pub fn wait_timeout(&mut self, dur: Duration) -> io::Result<Option<ExitStatus>> {
+        Ok(self.handle.wait_timeout(dur)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -751,6 +877,56 @@ impl Child {
             stderr: stderr,
         })
     }
+
+    /// Writes `input` to the stdin of the child and closes it, while
+    /// collecting all output on the stdout/stderr handles, then waits for the
+    /// child to exit, returning an `Output` instance.
+    ///
+    /// Writing and reading happen together, so the child can't deadlock
+    /// by filling up the pipe to its stdout while waiting for more input (or
+    /// the other way around). On Unix this is done on the current thread.
+    /// If the child exits or closes its stdin before reading all of `input`,
+    /// the rest is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the kind `InvalidInput` if `input` is not empty
+    /// but stdin was not captured with `Stdio::piped`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_communicate)]
+    /// use std::process::{Command, Stdio};
+    ///
+    /// let child = Command::new("sort")
+    ///                     .stdin(Stdio::piped())
+    ///                     .stdout(Stdio::piped())
+    ///                     .spawn()
+    ///                     .unwrap();
+    /// let output = child.communicate(b"banana\napple\n").unwrap();
+    /// assert_eq!(output.stdout, b"apple\nbanana\n");
+    /// ```
+    #[unstable(feature = "process_communicate", issue = "0")]
+    pub fn communicate(mut self, input: &[u8]) -> io::Result<Output> {
+        let stdin = self.stdin.take();
+        if stdin.is_none() && !input.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "the stdin of the child was not captured"));
+        }
+
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        communicate(stdin.map(|stdin| stdin.inner), input,
+                    self.stdout.take().map(|stdout| stdout.inner), &mut stdout,
+                    self.stderr.take().map(|stderr| stderr.inner), &mut stderr)?;
+
+        let status = self.wait()?;
+        Ok(Output {
+            status: status,
+            stdout: stdout,
+            stderr: stderr,
+        })
+    }
 }
 
 /// Terminates the current process with the specified exit code.
@@ -777,7 +953,8 @@ mod tests {
 
     use io::ErrorKind;
     use str;
-    use super::{Command, Output, Stdio};
+    use super::{Command, ExitStatus, Output, Stdio};
+    use time::{Duration, Instant};
 
     // FIXME(#10380) these tests should not all be ignored on android.
 
@@ -900,6 +1077,188 @@ mod tests {
         assert!(Command::new("/bin/ls").uid(0).gid(0).spawn().is_err());
     }
 
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn process_group_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        let mut p = Command::new("/bin/sh")
+                            .arg("-c").arg("read a")
+                            .stdin(Stdio::piped())
+                            .process_group(0)
+                            .spawn().unwrap();
+        let pid = p.id() as libc::pid_t;
+        assert_eq!(unsafe { libc::getpgid(pid) }, pid);
+        assert!(unsafe { libc::getpgid(pid) != libc::getpgrp() });
+        p.kill().unwrap();
+        p.wait().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn setsid_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        let mut p = Command::new("/bin/sh")
+                            .arg("-c").arg("read a")
+                            .stdin(Stdio::piped())
+                            .setsid(true)
+                            .spawn().unwrap();
+        let pid = p.id() as libc::pid_t;
+        // `spawn` returns once the child has called `exec`, after `setsid`.
+        assert_eq!(unsafe { libc::getsid(pid) }, pid);
+        p.kill().unwrap();
+        p.wait().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn rlimit_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c").arg("ulimit -n")
+           .rlimit(libc::RLIMIT_NOFILE as i32, 64, 64)
+           .stdout(Stdio::piped());
+        assert_eq!(run_output(cmd), "64\n");
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn map_fd_works() {
+        use os::unix::prelude::*;
+
+        let mut echo = Command::new("echo").arg("hello")
+                               .stdout(Stdio::piped())
+                               .spawn().unwrap();
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c").arg("cat <&5").stdout(Stdio::piped());
+        cmd.map_fd(echo.stdout.take().unwrap(), 5);
+        assert_eq!(run_output(cmd), "hello\n");
+        assert!(echo.wait().unwrap().success());
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn map_fd_keeps_error_pipe() {
+        use os::unix::prelude::*;
+        use fs::File;
+
+        // Map over all the low descriptors, one of which the pipe used to
+        // report errors from the child would get otherwise.
+        let mut cmd = Command::new("/no/such/program");
+        for fd in 3..128 {
+            cmd.map_fd(File::open("/dev/null").unwrap(), fd);
+        }
+        let err = cmd.spawn().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn try_wait() {
+        let mut p = if cfg!(windows) {
+            Command::new("cmd").args(&["/C", "exit 0"]).spawn().unwrap()
+        } else {
+            Command::new("true").spawn().unwrap()
+        };
+        let status = p.wait().unwrap();
+        assert_eq!(p.try_wait().unwrap(), Some(status));
+
+        let mut p = Command::new(if cfg!(windows) { "cmd" } else { "cat" })
+                            .stdin(Stdio::piped())
+                            .spawn().unwrap();
+        assert_eq!(p.try_wait().unwrap(), None);
+        drop(p.stdin.take());
+        let status = p.wait().unwrap();
+        assert_eq!(p.try_wait().unwrap(), Some(status));
+    }
+
+    #[test]
+    #[cfg_attr(any(windows, target_os = "android"), ignore)]
+    fn wait_timeout() {
+        let mut p = Command::new("sleep").arg("10").spawn().unwrap();
+        let start = Instant::now();
+        assert_eq!(p.wait_timeout(Duration::from_millis(100)).unwrap(), None);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        p.kill().unwrap();
+        assert!(p.wait_timeout(Duration::from_secs(10)).unwrap().is_some());
+
+        let mut p = Command::new("sleep").arg("0.1").spawn().unwrap();
+        let start = Instant::now();
+        let status = p.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+        assert!(status.success());
+        assert!(start.elapsed() < Duration::from_secs(30));
+
+        // A timeout too long to have a deadline is no timeout at all.
+        let mut p = Command::new("sleep").arg("0.1").spawn().unwrap();
+        let status = p.wait_timeout(Duration::new(u64::max_value(), 0)).unwrap().unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn signal_and_core_dump() {
+        use os::unix::process::ExitStatusExt;
+
+        let status = Command::new("/bin/sh").arg("-c").arg("kill -TERM $$")
+                                            .status().unwrap();
+        assert_eq!(status.signal(), Some(15));
+        assert_eq!(status.code(), None);
+        assert!(!status.core_dumped());
+        assert!(!ExitStatus::from_raw(0).core_dumped());
+        // SIGQUIT (3), with WCOREFLAG set.
+        assert!(ExitStatus::from_raw(0x83).core_dumped());
+    }
+
+    #[test]
+    #[cfg_attr(any(windows, target_os = "android"), ignore)]
+    fn pipeline() {
+        let mut echo = Command::new("echo").arg("hello")
+                               .stdout(Stdio::piped())
+                               .spawn().unwrap();
+        let mut cmd = Command::new("tr");
+        cmd.arg("a-z").arg("A-Z")
+           .stdin(Stdio::from(echo.stdout.take().unwrap()))
+           .stdout(Stdio::piped());
+        assert_eq!(run_output(cmd), "HELLO\n");
+        assert!(echo.wait().unwrap().success());
+    }
+
+    #[test]
+    #[cfg_attr(any(windows, target_os = "android"), ignore)]
+    fn communicate() {
+        // Enough to fill up the pipes in both directions.
+        let input: Vec<u8> = (0..1024 * 1024).map(|i| b"0123456789\n"[i % 11]).collect();
+        let child = Command::new("/bin/sh")
+                            .arg("-c").arg("cat; echo done >&2")
+                            .stdin(Stdio::piped())
+                            .stdout(Stdio::piped())
+                            .stderr(Stdio::piped())
+                            .spawn().unwrap();
+        let output = child.communicate(&input).unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout == input);
+        assert_eq!(output.stderr, b"done\n");
+
+        // The child going away early isn't an error.
+        let child = Command::new("true").stdin(Stdio::piped()).spawn().unwrap();
+        assert!(child.communicate(&input).unwrap().status.success());
+
+        let child = Command::new("true").spawn().unwrap();
+        let err = child.communicate(b"input").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_process_status() {
@@ -0,0 +1,337 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The parts of `fs::Watcher` which are shared between platforms.
+//!
+//! Platforms with a notification API feed the changes they are notified of
+//! through a `Coalescer`. Everywhere else, the `Watcher` here polls the
+//! metadata of the watched trees instead.
+
+use fs::WatchEvent;
+use path::PathBuf;
+
+#[cfg(target_os = "linux")]
+pub use self::coalesce::Coalescer;
+#[cfg(not(target_os = "linux"))]
+pub use self::poll::Watcher;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Change {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl Change {
+    fn event(self, path: PathBuf) -> WatchEvent {
+        match self {
+            Change::Created => WatchEvent::Created(path),
+            Change::Modified => WatchEvent::Modified(path),
+            Change::Removed => WatchEvent::Removed(path),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod coalesce {
+    use prelude::v1::*;
+
+    use collections::HashMap;
+    use fs::WatchEvent;
+    use io;
+    use path::PathBuf;
+    use sync::mpsc::Sender;
+    use time::{Duration, Instant};
+
+    use super::Change;
+
+    /// Changes which are held back for a while, so that the changes made to a
+    /// path in the meantime are delivered as one event.
+    pub struct Coalescer {
+        delay: Duration,
+        pending: HashMap<PathBuf, Pending>,
+    }
+
+    struct Pending {
+        change: Change,
+        /// When the first of the changes was seen; they are delivered `delay`
+        /// after that, however many more come in.
+        seen: Instant,
+    }
+
+    impl Coalescer {
+        pub fn new(delay: Duration) -> Coalescer {
+            Coalescer {
+                delay: delay,
+                pending: HashMap::new(),
+            }
+        }
+
+        pub fn add(&mut self, path: PathBuf, change: Change) {
+            let (change, seen) = match self.pending.get(&path) {
+                Some(pending) => (merge(pending.change, change), pending.seen),
+                None => (Some(change), Instant::now()),
+            };
+            match change {
+                Some(change) => {
+                    self.pending.insert(path, Pending { change: change, seen: seen });
+                }
+                None => {
+                    self.pending.remove(&path);
+                }
+            }
+        }
+
+        /// Drops the pending changes, when they have to be rescanned anyway.
+        pub fn clear(&mut self) {
+            self.pending.clear();
+        }
+
+        /// Returns how long to wait until the next change is due, if there is
+        /// any.
+        pub fn timeout(&self) -> Option<Duration> {
+            let now = Instant::now();
+            self.pending.values().map(|pending| pending.seen + self.delay).min().map(|due| {
+                if due > now { due - now } else { Duration::new(0, 0) }
+            })
+        }
+
+        /// Sends the changes which are due, in the order in which they were
+        /// first seen. Returns `false` if the receiver has hung up.
+        pub fn flush(&mut self, tx: &Sender<io::Result<WatchEvent>>) -> bool {
+            let now = Instant::now();
+            let mut due = self.pending.iter().filter(|&(_, pending)| {
+                pending.seen + self.delay <= now
+            }).map(|(path, pending)| {
+                (pending.seen, path.clone())
+            }).collect::<Vec<_>>();
+            due.sort();
+            for (_, path) in due {
+                let change = self.pending.remove(&path).unwrap().change;
+                if tx.send(Ok(change.event(path))).is_err() {
+                    return false
+                }
+            }
+            true
+        }
+    }
+
+    /// Merges a change into the change already pending for a path, returning
+    /// `None` if they cancel out.
+    fn merge(pending: Change, change: Change) -> Option<Change> {
+        match (pending, change) {
+            (Change::Created, Change::Removed) => None,
+            (Change::Created, _) => Some(Change::Created),
+            (_, Change::Removed) => Some(Change::Removed),
+            // A path which was removed and then created again was replaced.
+            (Change::Removed, _) | (Change::Modified, _) => Some(Change::Modified),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod poll {
+    use prelude::v1::*;
+
+    use collections::HashMap;
+    use fs::{self, WatchEvent, WalkOptions};
+    use io;
+    use path::{Path, PathBuf};
+    use sync::mpsc::Sender;
+    use sync::{Arc, Condvar, Mutex};
+    use thread::{self, JoinHandle};
+    use time::{Duration, SystemTime};
+
+    use super::Change;
+
+    /// A watcher which scans the watched trees every `delay`, and compares
+    /// the metadata of what it finds with the previous scan.
+    pub struct Watcher {
+        shared: Arc<Shared>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    struct Shared {
+        state: Mutex<State>,
+        cvar: Condvar,
+    }
+
+    struct State {
+        roots: Vec<Root>,
+        /// The id given to the next root, so that a scan made while a path
+        /// was watched again isn't compared with the fresh snapshot.
+        next_id: u64,
+        stopped: bool,
+    }
+
+    struct Root {
+        id: u64,
+        path: PathBuf,
+        recursive: bool,
+        snapshot: HashMap<PathBuf, Stamp>,
+    }
+
+    #[derive(PartialEq, Eq)]
+    struct Stamp {
+        is_dir: bool,
+        modified: Option<SystemTime>,
+        len: u64,
+    }
+
+    impl Stamp {
+        fn new(meta: &fs::Metadata) -> Stamp {
+            Stamp {
+                is_dir: meta.is_dir(),
+                modified: meta.modified().ok(),
+                len: meta.len(),
+            }
+        }
+    }
+
+    impl Watcher {
+        pub fn new(delay: Duration, tx: Sender<io::Result<WatchEvent>>)
+                   -> io::Result<Watcher> {
+            let shared = Arc::new(Shared {
+                state: Mutex::new(State { roots: Vec::new(), next_id: 0, stopped: false }),
+                cvar: Condvar::new(),
+            });
+            let thread = {
+                let shared = shared.clone();
+                thread::Builder::new().name("fs watcher".to_string()).spawn(move || {
+                    run(&shared, delay, tx)
+                })?
+            };
+            Ok(Watcher { shared: shared, thread: Some(thread) })
+        }
+
+        pub fn watch(&self, path: &Path, recursive: bool) -> io::Result<()> {
+            let snapshot = scan(path, recursive)?;
+            let mut state = self.shared.state.lock().unwrap();
+            state.roots.retain(|root| root.path != path);
+            let id = state.next_id;
+            state.next_id += 1;
+            state.roots.push(Root {
+                id: id,
+                path: path.to_path_buf(),
+                recursive: recursive,
+                snapshot: snapshot,
+            });
+            Ok(())
+        }
+
+        pub fn unwatch(&self, path: &Path) -> io::Result<()> {
+            let mut state = self.shared.state.lock().unwrap();
+            let len = state.roots.len();
+            state.roots.retain(|root| root.path != path);
+            if state.roots.len() == len {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "the path is not being watched"))
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            self.shared.state.lock().unwrap().stopped = true;
+            self.shared.cvar.notify_one();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    fn run(shared: &Shared, delay: Duration, tx: Sender<io::Result<WatchEvent>>) {
+        loop {
+            // The trees are scanned without holding the lock, so that
+            // `watch` and `unwatch` don't wait for a scan to finish.
+            let roots = {
+                let mut state = shared.state.lock().unwrap();
+                // The watcher may have been dropped while we were scanning,
+                // and then there's no one left to wake us up.
+                if !state.stopped {
+                    state = shared.cvar.wait_timeout(state, delay).unwrap().0;
+                }
+                if state.stopped {
+                    return
+                }
+                state.roots.iter().map(|root| {
+                    (root.id, root.path.clone(), root.recursive)
+                }).collect::<Vec<_>>()
+            };
+            let scans = roots.into_iter().map(|(id, path, recursive)| {
+                // A root which can't be scanned has been removed.
+                (id, scan(&path, recursive).unwrap_or(HashMap::new()))
+            }).collect::<Vec<_>>();
+
+            let mut events = Vec::new();
+            {
+                let mut state = shared.state.lock().unwrap();
+                for (id, snapshot) in scans {
+                    // Roots which were unwatched or watched again during
+                    // the scan are left alone.
+                    if let Some(root) = state.roots.iter_mut().find(|root| root.id == id) {
+                        events.extend(diff(&root.snapshot, &snapshot));
+                        root.snapshot = snapshot;
+                    }
+                }
+            }
+            for (path, change) in events {
+                if tx.send(Ok(change.event(path))).is_err() {
+                    return
+                }
+            }
+        }
+    }
+
+    /// The changes from one snapshot of a root to the next, parents first.
+    fn diff(old: &HashMap<PathBuf, Stamp>, new: &HashMap<PathBuf, Stamp>)
+            -> Vec<(PathBuf, Change)> {
+        let mut changes = Vec::new();
+        for (path, stamp) in new.iter() {
+            match old.get(path) {
+                None => changes.push((path.clone(), Change::Created)),
+                // Directories change along with their entries,
+                // which are reported on their own.
+                Some(old) if !stamp.is_dir && old != stamp => {
+                    changes.push((path.clone(), Change::Modified))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in old.keys() {
+            if !new.contains_key(path) {
+                changes.push((path.clone(), Change::Removed));
+            }
+        }
+        // Parents sort before their entries.
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes
+    }
+
+    fn scan(path: &Path, recursive: bool) -> io::Result<HashMap<PathBuf, Stamp>> {
+        let mut snapshot = HashMap::new();
+        let meta = fs::metadata(path)?;
+        snapshot.insert(path.to_path_buf(), Stamp::new(&meta));
+        if meta.is_dir() {
+            let mut opts = WalkOptions::new();
+            if !recursive {
+                opts.max_depth(1);
+            }
+            // Entries which vanish during the scan are left out.
+            for entry in opts.walk(path)?.filter_map(|entry| entry.ok()) {
+                if let Ok(meta) = entry.metadata() {
+                    snapshot.insert(entry.path(), Stamp::new(&meta));
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+}
@@ -30,6 +30,7 @@ pub mod args;
 pub mod at_exit_imp;
 pub mod backtrace;
 pub mod condvar;
+pub mod fs_watch;
 pub mod io;
 pub mod mutex;
 pub mod net;
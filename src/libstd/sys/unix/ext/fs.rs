@@ -12,10 +12,13 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use fmt;
 use fs::{self, Permissions, OpenOptions};
 use io;
 use libc;
+use ops::{Deref, DerefMut};
 use path::Path;
+use slice;
 use sys;
 use sys_common::{FromInner, AsInner, AsInnerMut};
 use sys::platform::fs::MetadataExt as UnixMetadataExt;
@@ -250,3 +253,335 @@ impl DirBuilderExt for fs::DirBuilder {
         self
     }
 }
+
+/// Unix-specific extensions to `File` for mapping files into memory.
+///
+/// A memory map makes a range of a file appear as a slice of bytes, which the
+/// operating system reads from the file as it is accessed, and writes back
+/// to it when it is modified. This avoids copying the contents of the file
+/// through a buffer, and only the parts which are accessed are ever read.
+///
+/// # Safety
+///
+/// Mapping a file is unsafe because the contents of the map can change, or
+/// even vanish, underneath the slice it is exposed as:
+///
+/// * Any write to the file, by this process through a `File` or through
+///   another map, or by any other process, is visible in the map. The
+///   compiler assumes that the bytes behind a `&[u8]` don't change while it
+///   is borrowed, so such a write is undefined behavior, even if it only
+///   seems to change the data read from the map.
+/// * Truncating the file makes accessing the pages of the map past its new
+///   end raise `SIGBUS`, which kills the process.
+///
+/// Callers must therefore ensure that the mapped range of the file is not
+/// modified or truncated for as long as the map is alive, for example by
+/// only mapping files which are private to the program or which are known
+/// to be immutable, or by holding a lock which every writer honors. Note
+/// that the advisory locks of `File::lock_shared` do not stop a process
+/// which does not take them from writing to the file.
+#[unstable(feature = "file_mmap", issue = "0")]
+pub trait FileExt {
+    /// Maps the whole file into memory, read-only.
+    ///
+    /// The length of the map is the length of the file when it is mapped.
+    /// It does not grow or shrink along with the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file was not opened for
+    /// reading, if it can't be mapped (because it is a pipe, for example),
+    /// or if it does not fit in the address space.
+    ///
+    /// # Safety
+    ///
+    /// The mapped file must not be modified or truncated while the map is
+    /// alive. See the trait documentation for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_mmap)]
+    /// use std::fs::File;
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let file = try!(File::open("artifact.rlib"));
+    /// let map = try!(unsafe { file.map() });
+    /// let lines = map.iter().filter(|&&b| b == b'\n').count();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_mmap", issue = "0")]
+    unsafe fn map(&self) -> io::Result<Mmap>;
+
+    /// Maps `len` bytes of the file starting at `offset` into memory,
+    /// read-only.
+    ///
+    /// The range does not need to be aligned to pages, but it should lie
+    /// within the file: accessing the parts of the map past the end of the
+    /// file raises `SIGBUS`.
+    ///
+    /// # Errors
+    ///
+    /// See `map`.
+    ///
+    /// # Safety
+    ///
+    /// The mapped range of the file must not be modified or truncated while
+    /// the map is alive, and must lie within the file. See the trait
+    /// documentation for details.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    unsafe fn map_range(&self, offset: u64, len: usize) -> io::Result<Mmap>;
+
+    /// Maps the whole file into memory, for reading and writing.
+    ///
+    /// Writes to the map are written back to the file by the operating
+    /// system at some point, or on a call to `MmapMut::flush`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file was not opened for
+    /// both reading and writing, in addition to the errors of `map`.
+    ///
+    /// # Safety
+    ///
+    /// The mapped file must not be modified or truncated by anyone else
+    /// while the map is alive. See the trait documentation for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_mmap)]
+    /// use std::fs::OpenOptions;
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let file = try!(OpenOptions::new().read(true).write(true).open("counts.bin"));
+    /// let mut map = try!(unsafe { file.map_mut() });
+    /// for byte in map.iter_mut() {
+    ///     *byte = byte.wrapping_add(1);
+    /// }
+    /// try!(map.flush());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_mmap", issue = "0")]
+    unsafe fn map_mut(&self) -> io::Result<MmapMut>;
+
+    /// Maps `len` bytes of the file starting at `offset` into memory, for
+    /// reading and writing.
+    ///
+    /// Mapping a range does not extend the file, so use `File::set_len`
+    /// first to write past its end.
+    ///
+    /// # Errors
+    ///
+    /// See `map_mut`.
+    ///
+    /// # Safety
+    ///
+    /// The mapped range of the file must not be modified or truncated by
+    /// anyone else while the map is alive, and must lie within the file. See
+    /// the trait documentation for details.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    unsafe fn map_range_mut(&self, offset: u64, len: usize) -> io::Result<MmapMut>;
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl FileExt for fs::File {
+    unsafe fn map(&self) -> io::Result<Mmap> {
+        let len = map_len(self)?;
+        self.map_range(0, len)
+    }
+
+    unsafe fn map_range(&self, offset: u64, len: usize) -> io::Result<Mmap> {
+        sys::fs::Mmap::new(self.as_inner(), offset, len, false).map(Mmap)
+    }
+
+    unsafe fn map_mut(&self) -> io::Result<MmapMut> {
+        let len = map_len(self)?;
+        self.map_range_mut(0, len)
+    }
+
+    unsafe fn map_range_mut(&self, offset: u64, len: usize) -> io::Result<MmapMut> {
+        sys::fs::Mmap::new(self.as_inner(), offset, len, true).map(MmapMut)
+    }
+}
+
+fn map_len(file: &fs::File) -> io::Result<usize> {
+    let len = file.metadata()?.len();
+    if len > usize::max_value() as u64 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "the file is too large to be mapped"))
+    }
+    Ok(len as usize)
+}
+
+/// Hints to the operating system about how a memory map will be accessed.
+///
+/// This is passed to `Mmap::advise` and `MmapMut::advise`, and corresponds
+/// to the `MADV_*` constants of `madvise`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[unstable(feature = "file_mmap", issue = "0")]
+pub enum Advice {
+    /// No particular access pattern; the default.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    Normal,
+    /// The pages will be accessed in order, so they can be read ahead
+    /// aggressively and freed soon after they are accessed.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    Sequential,
+    /// The pages will be accessed in random order, so reading ahead is
+    /// wasted.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    Random,
+    /// The pages will be accessed soon, so they can be read ahead now.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    WillNeed,
+    /// The pages will not be accessed soon, so they can be freed.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    DontNeed,
+}
+
+impl Advice {
+    fn to_raw(&self) -> libc::c_int {
+        match *self {
+            Advice::Normal => libc::MADV_NORMAL,
+            Advice::Sequential => libc::MADV_SEQUENTIAL,
+            Advice::Random => libc::MADV_RANDOM,
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+        }
+    }
+}
+
+/// A read-only memory map of a file, created by `FileExt::map` or
+/// `FileExt::map_range`.
+///
+/// The map dereferences to the mapped bytes, and is unmapped when dropped.
+/// It does not borrow the file it maps, which may be closed while the map
+/// is still in use.
+#[unstable(feature = "file_mmap", issue = "0")]
+pub struct Mmap(sys::fs::Mmap);
+
+impl Mmap {
+    /// Advises the operating system of how the whole map will be accessed.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        self.0.advise(advice.to_raw(), 0, self.0.len())
+    }
+
+    /// Advises the operating system of how `len` bytes of the map starting
+    /// at `offset` will be accessed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidInput` if the
+    /// range does not lie within the map.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn advise_range(&self, advice: Advice, offset: usize, len: usize) -> io::Result<()> {
+        self.0.advise(advice.to_raw(), offset, len)
+    }
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.0.ptr(), self.0.len()) }
+    }
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl fmt::Debug for Mmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Mmap")
+         .field("ptr", &self.0.ptr())
+         .field("len", &self.0.len())
+         .finish()
+    }
+}
+
+/// A memory map of a file which can be read and written, created by
+/// `FileExt::map_mut` or `FileExt::map_range_mut`.
+///
+/// The map dereferences to the mapped bytes, and is unmapped when dropped.
+/// Modifications are written back to the file by the operating system at
+/// some point before or after that; use `flush` to make sure that they have
+/// been.
+#[unstable(feature = "file_mmap", issue = "0")]
+pub struct MmapMut(sys::fs::Mmap);
+
+impl MmapMut {
+    /// Writes the modifications to the whole map back to the file, and
+    /// waits for them to reach the disk.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn flush(&self) -> io::Result<()> {
+        self.0.flush(0, self.0.len(), true)
+    }
+
+    /// Writes the modifications to `len` bytes of the map starting at
+    /// `offset` back to the file, and waits for them to reach the disk.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidInput` if the
+    /// range does not lie within the map.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn flush_range(&self, offset: usize, len: usize) -> io::Result<()> {
+        self.0.flush(offset, len, true)
+    }
+
+    /// Starts writing the modifications to the whole map back to the file,
+    /// without waiting for them to reach the disk.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn flush_async(&self) -> io::Result<()> {
+        self.0.flush(0, self.0.len(), false)
+    }
+
+    /// Advises the operating system of how the whole map will be accessed.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        self.0.advise(advice.to_raw(), 0, self.0.len())
+    }
+
+    /// Advises the operating system of how `len` bytes of the map starting
+    /// at `offset` will be accessed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidInput` if the
+    /// range does not lie within the map.
+    #[unstable(feature = "file_mmap", issue = "0")]
+    pub fn advise_range(&self, advice: Advice, offset: usize, len: usize) -> io::Result<()> {
+        self.0.advise(advice.to_raw(), offset, len)
+    }
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl Deref for MmapMut {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.0.ptr(), self.0.len()) }
+    }
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl DerefMut for MmapMut {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.0.ptr(), self.0.len()) }
+    }
+}
+
+#[unstable(feature = "file_mmap", issue = "0")]
+impl fmt::Debug for MmapMut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MmapMut")
+         .field("ptr", &self.0.ptr())
+         .field("len", &self.0.len())
+         .finish()
+    }
+}
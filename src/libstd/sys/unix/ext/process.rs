@@ -15,6 +15,7 @@
 use prelude::v1::*;
 
 use io;
+use libc;
 use os::unix::io::{FromRawFd, RawFd, AsRawFd, IntoRawFd};
 use process;
 use sys;
@@ -45,10 +46,80 @@ pub trait CommandExt {
     /// (the daemon) in the same session.
     #[unstable(feature = "process_session_leader", reason = "recently added",
                issue = "27811")]
-    #[rustc_deprecated(reason = "use `before_exec` instead",
+    #[rustc_deprecated(reason = "use `setsid` instead",
                        since = "1.9.0")]
     fn session_leader(&mut self, on: bool) -> &mut process::Command;
 
+    /// Creates a new session (cf. `setsid(2)`) for the child process, which
+    /// makes it the leader of a new process group without a controlling
+    /// terminal.
+    ///
+    /// This cannot be combined with `process_group`: a session leader cannot
+    /// move to another process group, so spawning fails.
+    #[unstable(feature = "process_setsid", issue = "0")]
+    fn setsid(&mut self, on: bool) -> &mut process::Command;
+
+    /// Puts the child process in the process group `pgroup` (cf.
+    /// `setpgid(2)`). If `pgroup` is 0, the child becomes the leader of a new
+    /// process group, whose id is the id of the child.
+    ///
+    /// This allows signalling the child together with its own children, for
+    /// example with `kill(-pgid, sig)`, and keeps terminal signals sent to
+    /// the process group of the parent from reaching it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_group)]
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// let child = Command::new("make").process_group(0).spawn().unwrap();
+    /// ```
+    #[unstable(feature = "process_group", issue = "0")]
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command;
+
+    /// Sets the soft and hard limits of a resource for the child process (cf.
+    /// `setrlimit(2)`). `resource` is one of the `RLIMIT_*` constants, such
+    /// as `libc::RLIMIT_NOFILE`, and `RLIM_INFINITY` means no limit.
+    ///
+    /// Failure to set a limit (for example, to raise a hard limit without
+    /// the privileges to do so) will cause the spawn to fail. Limits are set
+    /// before the user and group ids are changed.
+    #[unstable(feature = "process_rlimit", issue = "0")]
+    fn rlimit(&mut self, resource: i32, soft: u64, hard: u64) -> &mut process::Command;
+
+    /// Passes `fd` to the child process as the file descriptor `child_fd`.
+    ///
+    /// The `Command` takes ownership of `fd` and closes it when it is
+    /// dropped; the child gets its own copy, which is not closed on `exec`,
+    /// whatever the flags of `fd`. Descriptors may be mapped onto each other
+    /// (for example to swap two of them), and mapping onto 0, 1 or 2 takes
+    /// precedence over the stdio configuration. If several descriptors are
+    /// mapped to the same `child_fd`, the last one wins.
+    ///
+    /// Other descriptors are inherited as usual: those opened by the
+    /// standard library are close-on-exec, and any others which must not
+    /// reach the child can be closed in a `before_exec` closure.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_fd_mapping)]
+    /// use std::fs::File;
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// let config = File::open("config.toml").unwrap();
+    /// let child = Command::new("server")
+    ///                     .arg("--config-fd=3")
+    ///                     .map_fd(config, 3)
+    ///                     .spawn()
+    ///                     .unwrap();
+    /// ```
+    #[unstable(feature = "process_fd_mapping", issue = "0")]
+    fn map_fd<F: IntoRawFd>(&mut self, fd: F, child_fd: RawFd) -> &mut process::Command;
+
     /// Schedules a closure to be run just before the `exec` function is
     /// invoked.
     ///
@@ -117,6 +188,27 @@ impl CommandExt for process::Command {
         self
     }
 
+    fn setsid(&mut self, on: bool) -> &mut process::Command {
+        self.as_inner_mut().session_leader(on);
+        self
+    }
+
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command {
+        self.as_inner_mut().pgroup(pgroup);
+        self
+    }
+
+    fn rlimit(&mut self, resource: i32, soft: u64, hard: u64) -> &mut process::Command {
+        self.as_inner_mut().rlimit(resource, soft as libc::rlim_t, hard as libc::rlim_t);
+        self
+    }
+
+    fn map_fd<F: IntoRawFd>(&mut self, fd: F, child_fd: RawFd) -> &mut process::Command {
+        let fd = sys::fd::FileDesc::new(fd.into_raw_fd());
+        self.as_inner_mut().map_fd(fd, child_fd);
+        self
+    }
+
     fn before_exec<F>(&mut self, f: F) -> &mut process::Command
         where F: FnMut() -> io::Result<()> + Send + Sync + 'static
     {
@@ -140,6 +232,11 @@ pub trait ExitStatusExt {
     /// If the process was terminated by a signal, returns that signal.
     #[stable(feature = "rust1", since = "1.0.0")]
     fn signal(&self) -> Option<i32>;
+
+    /// Returns whether the process dumped core when it was terminated by a
+    /// signal.
+    #[unstable(feature = "exit_status_core_dumped", issue = "0")]
+    fn core_dumped(&self) -> bool;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -151,6 +248,10 @@ impl ExitStatusExt for process::ExitStatus {
     fn signal(&self) -> Option<i32> {
         self.as_inner().signal()
     }
+
+    fn core_dumped(&self) -> bool {
+        self.as_inner().core_dumped()
+    }
 }
 
 #[stable(feature = "process_extensions", since = "1.2.0")]
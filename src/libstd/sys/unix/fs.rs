@@ -20,6 +20,7 @@ use path::{Path, PathBuf};
 use ptr;
 use sync::Arc;
 use sys::fd::FileDesc;
+use sys::os;
 use sys::time::SystemTime;
 use sys::{cvt, cvt_r};
 use sys_common::{AsInner, FromInner};
@@ -38,6 +39,15 @@ use libc::{stat as stat64, fstat as fstat64, lstat as lstat64, off_t as off64_t,
               target_os = "emscripten",
               target_os = "solaris")))]
 use libc::{readdir_r as readdir64_r};
+#[cfg(target_os = "linux")]
+use libc::mmap64;
+#[cfg(not(target_os = "linux"))]
+use libc::mmap as mmap64;
+
+#[cfg(target_os = "linux")]
+pub use sys::inotify::Watcher;
+#[cfg(not(target_os = "linux"))]
+pub use sys_common::fs_watch::Watcher;
 
 pub struct File(FileDesc);
 
@@ -453,6 +463,50 @@ impl File {
         unsafe fn os_datasync(fd: c_int) -> c_int { libc::fsync(fd) }
     }
 
+    #[cfg(not(target_os = "solaris"))]
+    pub fn lock(&self, exclusive: bool, block: bool) -> io::Result<()> {
+        let mut op = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+        if !block {
+            op |= libc::LOCK_NB;
+        }
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), op) })?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "solaris"))]
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), libc::LOCK_UN) })?;
+        Ok(())
+    }
+
+    // Solaris has no flock, so whole-file record locks are used instead.
+    #[cfg(target_os = "solaris")]
+    pub fn lock(&self, exclusive: bool, block: bool) -> io::Result<()> {
+        let ty = if exclusive { libc::F_WRLCK } else { libc::F_RDLCK };
+        let cmd = if block { libc::F_SETLKW } else { libc::F_SETLK };
+        match self.setlk(ty, cmd) {
+            // A lock held by another process is reported as either of these.
+            Err(ref e) if e.raw_os_error() == Some(libc::EACCES) => {
+                Err(io::Error::from_raw_os_error(libc::EAGAIN))
+            }
+            result => result,
+        }
+    }
+
+    #[cfg(target_os = "solaris")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.setlk(libc::F_UNLCK, libc::F_SETLK)
+    }
+
+    #[cfg(target_os = "solaris")]
+    fn setlk(&self, ty: c_int, cmd: c_int) -> io::Result<()> {
+        let mut flock: libc::flock = unsafe { mem::zeroed() };
+        flock.l_type = ty as libc::c_short;
+        flock.l_whence = libc::SEEK_SET as libc::c_short;
+        cvt_r(|| unsafe { libc::fcntl(self.0.raw(), cmd, &flock) })?;
+        Ok(())
+    }
+
     pub fn truncate(&self, size: u64) -> io::Result<()> {
         #[cfg(target_os = "android")]
         return ::sys::android::ftruncate64(self.0.raw(), size);
@@ -496,6 +550,98 @@ impl File {
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
 
+/// A memory map of a range of a file, which is unmapped when dropped.
+pub struct Mmap {
+    /// The start of the mapping, which is aligned to a page.
+    ptr: *mut libc::c_void,
+    /// The length of the mapping.
+    len: usize,
+    /// The offset of the mapped range of the file from the start of the
+    /// mapping.
+    delta: usize,
+}
+
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    pub fn new(file: &File, offset: u64, len: usize, writable: bool) -> io::Result<Mmap> {
+        let delta = (offset % os::page_size() as u64) as usize;
+        if len == 0 {
+            // Nothing can be mapped, but a dangling pointer makes for a
+            // valid empty slice.
+            return Ok(Mmap { ptr: 1 as *mut libc::c_void, len: 0, delta: 0 })
+        }
+        let map_len = match len.checked_add(delta) {
+            Some(map_len) => map_len,
+            None => return Err(Error::new(ErrorKind::InvalidInput,
+                                          "the mapped range is too long")),
+        };
+        let prot = if writable {
+            libc::PROT_READ | libc::PROT_WRITE
+        } else {
+            libc::PROT_READ
+        };
+        let ptr = unsafe {
+            mmap64(ptr::null_mut(), map_len, prot, libc::MAP_SHARED,
+                   file.0.raw(), (offset - delta as u64) as _)
+        };
+        if ptr == libc::MAP_FAILED {
+            Err(Error::last_os_error())
+        } else {
+            Ok(Mmap { ptr: ptr, len: map_len, delta: delta })
+        }
+    }
+
+    pub fn ptr(&self) -> *mut u8 {
+        unsafe { (self.ptr as *mut u8).offset(self.delta as isize) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len - self.delta
+    }
+
+    /// Returns the page-aligned start and length of the pages which hold
+    /// `len` bytes from `offset` in the mapped range.
+    fn pages(&self, offset: usize, len: usize) -> io::Result<(*mut libc::c_void, usize)> {
+        if offset > self.len() || len > self.len() - offset {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "the range is not within the map"))
+        }
+        let offset = offset + self.delta;
+        let aligned = offset - offset % os::page_size();
+        let ptr = unsafe { (self.ptr as *mut u8).offset(aligned as isize) };
+        Ok((ptr as *mut libc::c_void, len + offset - aligned))
+    }
+
+    pub fn flush(&self, offset: usize, len: usize, sync: bool) -> io::Result<()> {
+        let (ptr, len) = self.pages(offset, len)?;
+        if len == 0 {
+            return Ok(())
+        }
+        let flags = if sync { libc::MS_SYNC } else { libc::MS_ASYNC };
+        cvt(unsafe { libc::msync(ptr, len, flags) })?;
+        Ok(())
+    }
+
+    pub fn advise(&self, advice: c_int, offset: usize, len: usize) -> io::Result<()> {
+        let (ptr, len) = self.pages(offset, len)?;
+        if len == 0 {
+            return Ok(())
+        }
+        cvt(unsafe { libc::madvise(ptr, len, advice) })?;
+        Ok(())
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            unsafe { libc::munmap(self.ptr, self.len); }
+        }
+    }
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder {
         DirBuilder { mode: 0o777 }
@@ -681,6 +827,16 @@ pub fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    File::open(p, &opts)?.fsync()
+}
+
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    symlink(&readlink(from)?, to)
+}
+
 pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
     let src = cstr(src)?;
     let dst = cstr(dst)?;
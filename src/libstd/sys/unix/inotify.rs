@@ -0,0 +1,365 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Watching the file system for changes with inotify.
+//!
+//! `Inotify` is a thin wrapper around an inotify descriptor, and `Watcher`
+//! is the implementation of `fs::Watcher` on top of it: a thread reads the
+//! events of every watch, translates them to paths and hands them to a
+//! `Coalescer` for delivery.
+
+#![allow(non_camel_case_types)]
+
+use prelude::v1::*;
+use os::unix::prelude::*;
+
+use cmp;
+use collections::HashMap;
+use ffi::{CString, OsString};
+use fs::{self, WatchEvent};
+use i32;
+use io;
+use libc::{self, c_char, c_int};
+use mem;
+use path::{Path, PathBuf};
+use ptr;
+use slice;
+use sync::mpsc::Sender;
+use sync::{Arc, Mutex};
+use sys::cvt;
+use sys::fd::FileDesc;
+use sys::pipe::{self, AnonPipe};
+use sys_common::fs_watch::{Change, Coalescer};
+use thread::{self, JoinHandle};
+use time::Duration;
+
+pub const IN_ACCESS: u32 = 0x00000001;
+pub const IN_MODIFY: u32 = 0x00000002;
+pub const IN_ATTRIB: u32 = 0x00000004;
+pub const IN_CLOSE_WRITE: u32 = 0x00000008;
+pub const IN_CLOSE_NOWRITE: u32 = 0x00000010;
+pub const IN_OPEN: u32 = 0x00000020;
+pub const IN_MOVED_FROM: u32 = 0x00000040;
+pub const IN_MOVED_TO: u32 = 0x00000080;
+pub const IN_CREATE: u32 = 0x00000100;
+pub const IN_DELETE: u32 = 0x00000200;
+pub const IN_DELETE_SELF: u32 = 0x00000400;
+pub const IN_MOVE_SELF: u32 = 0x00000800;
+pub const IN_ALL_EVENTS: u32 = 0x00000fff;
+pub const IN_UNMOUNT: u32 = 0x00002000;
+pub const IN_Q_OVERFLOW: u32 = 0x00004000;
+pub const IN_IGNORED: u32 = 0x00008000;
+pub const IN_ONLYDIR: u32 = 0x01000000;
+pub const IN_DONT_FOLLOW: u32 = 0x02000000;
+pub const IN_EXCL_UNLINK: u32 = 0x04000000;
+pub const IN_MASK_ADD: u32 = 0x20000000;
+pub const IN_ISDIR: u32 = 0x40000000;
+pub const IN_ONESHOT: u32 = 0x80000000;
+
+extern {
+    fn inotify_init() -> c_int;
+    fn inotify_add_watch(fd: c_int, pathname: *const c_char, mask: u32) -> c_int;
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int;
+}
+
+/// The header of an event read from an inotify descriptor, which is
+/// followed by `len` bytes holding the nul-terminated and padded name.
+#[repr(C)]
+struct inotify_event {
+    wd: c_int,
+    mask: u32,
+    cookie: u32,
+    len: u32,
+}
+
+#[derive(Debug)]
+pub struct Event {
+    pub wd: c_int,
+    pub mask: u32,
+    pub cookie: u32,
+    pub name: Option<OsString>,
+}
+
+pub struct Inotify(FileDesc);
+
+impl Inotify {
+    pub fn new() -> io::Result<Inotify> {
+        // inotify_init1 was only added in 2.6.27, and we support 2.6.18.
+        weak! { fn inotify_init1(c_int) -> c_int }
+        if let Some(init) = inotify_init1.get() {
+            match cvt(unsafe { init(libc::O_CLOEXEC) }) {
+                Ok(fd) => return Ok(Inotify(FileDesc::new(fd))),
+                Err(ref e) if e.raw_os_error() == Some(libc::ENOSYS) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let fd = FileDesc::new(cvt(unsafe { inotify_init() })?);
+        fd.set_cloexec();
+        Ok(Inotify(fd))
+    }
+
+    pub fn add_watch(&self, path: &Path, mask: u32) -> io::Result<c_int> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        cvt(unsafe { inotify_add_watch(self.0.raw(), path.as_ptr(), mask) })
+    }
+
+    pub fn rm_watch(&self, wd: c_int) -> io::Result<()> {
+        cvt(unsafe { inotify_rm_watch(self.0.raw(), wd) })?;
+        Ok(())
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.0.set_nonblocking(nonblocking)
+    }
+
+    /// Reads the events which are available, blocking until there is at
+    /// least one unless the descriptor is non-blocking, and appends them
+    /// to `events`.
+    pub fn read_events(&self, events: &mut Vec<Event>) -> io::Result<()> {
+        // Events are aligned to their header, and the buffer is large
+        // enough for any single event (the header and a name of up to
+        // NAME_MAX bytes).
+        let mut aligned = [0 as c_int; 1024];
+        let buf = unsafe {
+            slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8,
+                                      aligned.len() * mem::size_of::<c_int>())
+        };
+        let n = self.0.read(buf)?;
+        let header = mem::size_of::<inotify_event>();
+        let mut offset = 0;
+        while offset + header <= n {
+            let event = unsafe {
+                ptr::read(buf.as_ptr().offset(offset as isize) as *const inotify_event)
+            };
+            let name = &buf[offset + header..offset + header + event.len as usize];
+            let name = match name.iter().position(|&b| b == 0).unwrap_or(name.len()) {
+                0 => None,
+                len => Some(OsString::from_vec(name[..len].to_vec())),
+            };
+            events.push(Event {
+                wd: event.wd,
+                mask: event.mask,
+                cookie: event.cookie,
+                name: name,
+            });
+            offset += header + event.len as usize;
+        }
+        Ok(())
+    }
+
+    pub fn fd(&self) -> &FileDesc { &self.0 }
+
+    pub fn into_fd(self) -> FileDesc { self.0 }
+}
+
+/// The events which `Watcher` watches for.
+const WATCH_MASK: u32 = IN_MODIFY | IN_ATTRIB | IN_CREATE | IN_DELETE | IN_MOVED_FROM |
+                        IN_MOVED_TO | IN_DELETE_SELF | IN_MOVE_SELF;
+
+pub struct Watcher {
+    shared: Arc<Shared>,
+    /// The write end of a pipe which the thread polls along with the
+    /// inotify descriptor, and which is closed to stop it.
+    stop: Option<AnonPipe>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    inotify: Inotify,
+    watches: Mutex<Watches>,
+}
+
+struct Watches {
+    /// The path of each watch descriptor, and whether the directories below
+    /// it are watched too.
+    paths: HashMap<c_int, (PathBuf, bool)>,
+    /// The paths given to `watch`.
+    roots: HashMap<PathBuf, bool>,
+}
+
+impl Watcher {
+    pub fn new(delay: Duration, tx: Sender<io::Result<WatchEvent>>) -> io::Result<Watcher> {
+        let shared = Arc::new(Shared {
+            inotify: Inotify::new()?,
+            watches: Mutex::new(Watches { paths: HashMap::new(), roots: HashMap::new() }),
+        });
+        let (stop_rx, stop_tx) = pipe::anon_pipe()?;
+        let thread = {
+            let shared = shared.clone();
+            thread::Builder::new().name("fs watcher".to_string()).spawn(move || {
+                shared.run(stop_rx, delay, tx)
+            })?
+        };
+        Ok(Watcher {
+            shared: shared,
+            stop: Some(stop_tx),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn watch(&self, path: &Path, recursive: bool) -> io::Result<()> {
+        let mut watches = self.shared.watches.lock().unwrap();
+        self.shared.add_watches(&mut watches, path, recursive)?;
+        watches.roots.insert(path.to_path_buf(), recursive);
+        Ok(())
+    }
+
+    pub fn unwatch(&self, path: &Path) -> io::Result<()> {
+        let mut watches = self.shared.watches.lock().unwrap();
+        let recursive = match watches.roots.remove(path) {
+            Some(recursive) => recursive,
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "the path is not being watched")),
+        };
+        self.shared.remove_watches(&mut watches, path, recursive);
+        Ok(())
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Shared {
+    /// Watches `path`, and every directory below it if `recursive`. Only
+    /// errors watching `path` itself are returned, as the directories below
+    /// it may be removed at any time.
+    fn add_watches(&self, watches: &mut Watches, path: &Path, recursive: bool)
+                   -> io::Result<()> {
+        let wd = self.inotify.add_watch(path, WATCH_MASK)?;
+        watches.paths.insert(wd, (path.to_path_buf(), recursive));
+        if recursive && path.is_dir() {
+            for entry in fs::walk_dir(path)?.filter_map(|entry| entry.ok()) {
+                if entry.file_type().map(|ty| ty.is_dir()).unwrap_or(false) {
+                    let path = entry.path();
+                    if let Ok(wd) = self.inotify.add_watch(&path, WATCH_MASK) {
+                        watches.paths.insert(wd, (path, true));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops watching `path`, and every directory below it if `recursive`.
+    fn remove_watches(&self, watches: &mut Watches, path: &Path, recursive: bool) {
+        let wds = watches.paths.iter().filter(|&(_, &(ref watched, _))| {
+            watched == path || (recursive && watched.starts_with(path))
+        }).map(|(&wd, _)| wd).collect::<Vec<_>>();
+        for wd in wds {
+            watches.paths.remove(&wd);
+            let _ = self.inotify.rm_watch(wd);
+        }
+    }
+
+    fn run(&self, stop: AnonPipe, delay: Duration, tx: Sender<io::Result<WatchEvent>>) {
+        let mut coalescer = Coalescer::new(delay);
+        let mut events = Vec::new();
+        loop {
+            let timeout = coalescer.timeout().map_or(-1, |timeout| {
+                // Round up, so as not to wake up just before a change is due.
+                let ms = timeout.as_secs().saturating_mul(1000)
+                                .saturating_add((timeout.subsec_nanos() as u64 + 999_999) /
+                                                1_000_000);
+                cmp::min(ms, i32::MAX as u64) as c_int
+            });
+            let mut fds = [
+                libc::pollfd { fd: self.inotify.fd().raw(), events: libc::POLLIN, revents: 0 },
+                libc::pollfd { fd: stop.fd().raw(), events: libc::POLLIN, revents: 0 },
+            ];
+            match cvt(unsafe { libc::poll(fds.as_mut_ptr(), 2, timeout) }) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    return
+                }
+            }
+            // The write end is only ever closed.
+            if fds[1].revents != 0 {
+                return
+            }
+
+            if fds[0].revents != 0 {
+                match self.inotify.read_events(&mut events) {
+                    Ok(()) => {}
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return
+                    }
+                }
+                let mut watches = self.watches.lock().unwrap();
+                for event in events.drain(..) {
+                    if event.mask & IN_Q_OVERFLOW != 0 {
+                        coalescer.clear();
+                        if tx.send(Ok(WatchEvent::Rescan)).is_err() {
+                            return
+                        }
+                    } else {
+                        self.handle(&mut watches, &mut coalescer, event);
+                    }
+                }
+            }
+
+            if !coalescer.flush(&tx) {
+                return
+            }
+        }
+    }
+
+    fn handle(&self, watches: &mut Watches, coalescer: &mut Coalescer, event: Event) {
+        if event.mask & IN_IGNORED != 0 {
+            watches.paths.remove(&event.wd);
+            return
+        }
+        let (dir, recursive) = match watches.paths.get(&event.wd) {
+            Some(&(ref dir, recursive)) => (dir.clone(), recursive),
+            None => return,
+        };
+        let path = match event.name {
+            Some(ref name) => dir.join(name),
+            None => dir.clone(),
+        };
+        let is_dir = event.mask & IN_ISDIR != 0;
+
+        if event.mask & (IN_CREATE | IN_MOVED_TO) != 0 {
+            coalescer.add(path.clone(), Change::Created);
+            if is_dir && recursive && self.add_watches(watches, &path, true).is_ok() {
+                // Entries may have been created in the directory before it
+                // was watched.
+                if let Ok(walk) = fs::walk_dir(&path) {
+                    for entry in walk.filter_map(|entry| entry.ok()) {
+                        coalescer.add(entry.path(), Change::Created);
+                    }
+                }
+            }
+        } else if event.mask & (IN_DELETE | IN_MOVED_FROM) != 0 {
+            coalescer.add(path.clone(), Change::Removed);
+            if is_dir && recursive {
+                // The watches of a directory which was moved away would
+                // report its changes under its old path.
+                self.remove_watches(watches, &path, true);
+            }
+        } else if event.mask & (IN_MODIFY | IN_ATTRIB) != 0 {
+            coalescer.add(path, Change::Modified);
+        } else if event.mask & (IN_DELETE_SELF | IN_MOVE_SELF) != 0 {
+            // The removal of any other directory is reported by its parent.
+            if watches.roots.contains_key(&dir) {
+                coalescer.add(dir, Change::Removed);
+            }
+        }
+    }
+}
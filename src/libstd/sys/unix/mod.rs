@@ -37,11 +37,14 @@ pub mod condvar;
 pub mod ext;
 pub mod fd;
 pub mod fs;
+#[cfg(target_os = "linux")]
+pub mod inotify;
 pub mod mutex;
 pub mod net;
 pub mod os;
 pub mod os_str;
 pub mod pipe;
+pub mod poll;
 pub mod process;
 pub mod rand;
 pub mod rwlock;
@@ -10,10 +10,8 @@
 
 use prelude::v1::*;
 
-use cmp;
 use io;
 use libc::{self, c_int};
-use mem;
 use sys::cvt_r;
 use sys::fd::FileDesc;
 
@@ -77,49 +75,81 @@ pub fn read2(p1: AnonPipe,
              v1: &mut Vec<u8>,
              p2: AnonPipe,
              v2: &mut Vec<u8>) -> io::Result<()> {
-    // Set both pipes into nonblocking mode as we're gonna be reading from both
-    // in the `select` loop below, and we wouldn't want one to block the other!
-    let p1 = p1.into_fd();
-    let p2 = p2.into_fd();
-    p1.set_nonblocking(true);
-    p2.set_nonblocking(true);
-
-    let max = cmp::max(p1.raw(), p2.raw());
+    communicate(None, &[], Some(p1), v1, Some(p2), v2)
+}
+
+/// Writes `input` to `stdin` (closing it afterwards) while reading `stdout`
+/// and `stderr` to their ends, all on the current thread.
+///
+/// If the reader of `stdin` goes away before all of `input` is written, the
+/// rest is silently dropped.
+pub fn communicate(stdin: Option<AnonPipe>,
+                   input: &[u8],
+                   stdout: Option<AnonPipe>,
+                   out: &mut Vec<u8>,
+                   stderr: Option<AnonPipe>,
+                   err: &mut Vec<u8>) -> io::Result<()> {
+    // All of the pipes are put into nonblocking mode as we're gonna be
+    // waiting on them together in the `poll` loop below, and we wouldn't
+    // want one to block the others!
+    let nonblocking = |pipe: AnonPipe| {
+        let fd = pipe.into_fd();
+        fd.set_nonblocking(true);
+        fd
+    };
+    let mut input = input;
+    let mut stdin = if input.is_empty() { None } else { stdin.map(&nonblocking) };
+    let mut stdout = stdout.map(&nonblocking);
+    let mut stderr = stderr.map(&nonblocking);
+
+    // Read as much as we can from a pipe, ignoring EWOULDBLOCK or EAGAIN,
+    // and returning whether we hit EOF.
+    let read = |fd: &FileDesc, dst: &mut Vec<u8>| {
+        match fd.read_to_end(dst) {
+            Ok(_) => Ok(true),
+            Err(ref e) if is_would_block(e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    };
+
     loop {
-        // wait for either pipe to become readable using `select`
-        cvt_r(|| unsafe {
-            let mut read: libc::fd_set = mem::zeroed();
-            libc::FD_SET(p1.raw(), &mut read);
-            libc::FD_SET(p2.raw(), &mut read);
-            libc::select(max + 1, &mut read, 0 as *mut _, 0 as *mut _,
-                         0 as *mut _)
-        })?;
-
-        // Read as much as we can from each pipe, ignoring EWOULDBLOCK or
-        // EAGAIN. If we hit EOF, then this will happen because the underlying
-        // reader will return Ok(0), in which case we'll see `Ok` ourselves. In
-        // this case we flip the other fd back into blocking mode and read
-        // whatever's leftover on that file descriptor.
-        let read = |fd: &FileDesc, dst: &mut Vec<u8>| {
-            match fd.read_to_end(dst) {
-                Ok(_) => Ok(true),
-                Err(e) => {
-                    if e.raw_os_error() == Some(libc::EWOULDBLOCK) ||
-                       e.raw_os_error() == Some(libc::EAGAIN) {
-                        Ok(false)
-                    } else {
-                        Err(e)
-                    }
-                }
+        let mut fds = [libc::pollfd { fd: -1, events: 0, revents: 0 }; 3];
+        if let Some(ref fd) = stdin {
+            fds[0] = libc::pollfd { fd: fd.raw(), events: libc::POLLOUT, revents: 0 };
+        }
+        if let Some(ref fd) = stdout {
+            fds[1] = libc::pollfd { fd: fd.raw(), events: libc::POLLIN, revents: 0 };
+        }
+        if let Some(ref fd) = stderr {
+            fds[2] = libc::pollfd { fd: fd.raw(), events: libc::POLLIN, revents: 0 };
+        }
+        if fds.iter().all(|pfd| pfd.fd == -1) {
+            return Ok(())
+        }
+        // Negative descriptors are ignored by `poll`.
+        cvt_r(|| unsafe { libc::poll(fds.as_mut_ptr(), 3, -1) })?;
+
+        if fds[0].revents != 0 {
+            match stdin.as_ref().unwrap().write(input) {
+                Ok(n) => input = &input[n..],
+                Err(ref e) if is_would_block(e) => {}
+                Err(ref e) if e.raw_os_error() == Some(libc::EPIPE) => input = &[],
+                Err(e) => return Err(e),
             }
-        };
-        if read(&p1, v1)? {
-            p2.set_nonblocking(false);
-            return p2.read_to_end(v2).map(|_| ());
+            if input.is_empty() {
+                // Closing stdin lets the child see EOF.
+                stdin = None;
+            }
+        }
+        if fds[1].revents != 0 && read(stdout.as_ref().unwrap(), out)? {
+            stdout = None;
         }
-        if read(&p2, v2)? {
-            p1.set_nonblocking(false);
-            return p1.read_to_end(v1).map(|_| ());
+        if fds[2].revents != 0 && read(stderr.as_ref().unwrap(), err)? {
+            stderr = None;
         }
     }
 }
+
+fn is_would_block(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::EWOULDBLOCK) || e.raw_os_error() == Some(libc::EAGAIN)
+}
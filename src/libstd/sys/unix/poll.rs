@@ -0,0 +1,278 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Waiting for readiness of many file descriptors at once.
+//!
+//! On Linux and Android this uses epoll, and everywhere else (or if the
+//! kernel does not support epoll) it falls back to `poll(2)` over a list of
+//! registered descriptors. Both are level-triggered.
+
+use prelude::v1::*;
+
+use cmp;
+use io;
+use libc::{self, c_int};
+use sync::Mutex;
+use sys::cvt_r;
+use time::Duration;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub token: usize,
+    pub readable: bool,
+    pub writable: bool,
+    pub hangup: bool,
+    pub error: bool,
+}
+
+pub struct Poll {
+    imp: Imp,
+}
+
+enum Imp {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Epoll(epoll::Epoll),
+    Poll(Mutex<Registrations>),
+}
+
+/// The descriptors registered with the `poll(2)` fallback, and their tokens.
+struct Registrations {
+    fds: Vec<libc::pollfd>,
+    tokens: Vec<usize>,
+    /// Where to start looking for ready descriptors: just after the last one
+    /// reported, so that each gets its turn when more are ready than fit.
+    next: usize,
+}
+
+impl Poll {
+    pub fn new() -> io::Result<Poll> {
+        let imp = match new_epoll()? {
+            Some(imp) => imp,
+            None => {
+                Imp::Poll(Mutex::new(Registrations {
+                    fds: Vec::new(),
+                    tokens: Vec::new(),
+                    next: 0,
+                }))
+            }
+        };
+        Ok(Poll { imp: imp })
+    }
+
+    pub fn register(&self, fd: c_int, token: usize, readable: bool, writable: bool)
+                    -> io::Result<()> {
+        match self.imp {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Imp::Epoll(ref epoll) => epoll.ctl(libc::EPOLL_CTL_ADD, fd, token, readable, writable),
+            Imp::Poll(ref registrations) => {
+                let mut registrations = registrations.lock().unwrap();
+                if registrations.fds.iter().any(|pfd| pfd.fd == fd) {
+                    return Err(io::Error::from_raw_os_error(libc::EEXIST));
+                }
+                registrations.fds.push(libc::pollfd {
+                    fd: fd,
+                    events: poll_events(readable, writable),
+                    revents: 0,
+                });
+                registrations.tokens.push(token);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn reregister(&self, fd: c_int, token: usize, readable: bool, writable: bool)
+                      -> io::Result<()> {
+        match self.imp {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Imp::Epoll(ref epoll) => epoll.ctl(libc::EPOLL_CTL_MOD, fd, token, readable, writable),
+            Imp::Poll(ref registrations) => {
+                let mut registrations = registrations.lock().unwrap();
+                let i = match registrations.fds.iter().position(|pfd| pfd.fd == fd) {
+                    Some(i) => i,
+                    None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+                };
+                registrations.fds[i].events = poll_events(readable, writable);
+                registrations.tokens[i] = token;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn deregister(&self, fd: c_int) -> io::Result<()> {
+        match self.imp {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Imp::Epoll(ref epoll) => epoll.ctl(libc::EPOLL_CTL_DEL, fd, 0, false, false),
+            Imp::Poll(ref registrations) => {
+                let mut registrations = registrations.lock().unwrap();
+                let i = match registrations.fds.iter().position(|pfd| pfd.fd == fd) {
+                    Some(i) => i,
+                    None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+                };
+                registrations.fds.swap_remove(i);
+                registrations.tokens.swap_remove(i);
+                Ok(())
+            }
+        }
+    }
+
+    /// Waits for at most `max` events (which must not be zero), appending
+    /// them to `events`.
+    pub fn wait(&self, events: &mut Vec<Event>, max: usize, timeout: Option<Duration>)
+                -> io::Result<()> {
+        let max = cmp::min(max, c_int::max_value() as usize);
+        let timeout = timeout_ms(timeout);
+        match self.imp {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Imp::Epoll(ref epoll) => epoll.wait(events, max, timeout),
+            Imp::Poll(ref registrations) => {
+                // Don't hold the lock while blocked so that other threads can
+                // still change the registrations; the changes are picked up by
+                // the next call.
+                let (mut fds, tokens, mut next) = {
+                    let registrations = registrations.lock().unwrap();
+                    (registrations.fds.clone(), registrations.tokens.clone(), registrations.next)
+                };
+                cvt_r(|| unsafe {
+                    libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout)
+                })?;
+                let start = next;
+                let mut n = 0;
+                for i in (0..fds.len()).map(|i| (start + i) % fds.len()) {
+                    let pfd = &fds[i];
+                    if pfd.revents == 0 {
+                        continue
+                    }
+                    if n == max {
+                        break
+                    }
+                    events.push(Event {
+                        token: tokens[i],
+                        readable: pfd.revents & libc::POLLIN != 0,
+                        writable: pfd.revents & libc::POLLOUT != 0,
+                        hangup: pfd.revents & libc::POLLHUP != 0,
+                        error: pfd.revents & (libc::POLLERR | libc::POLLNVAL) != 0,
+                    });
+                    n += 1;
+                    next = i + 1;
+                }
+                // The registrations may have changed in the meantime, which
+                // only makes this a worse guess.
+                registrations.lock().unwrap().next = next;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn new_epoll() -> io::Result<Option<Imp>> {
+    match epoll::Epoll::new() {
+        Ok(epoll) => Ok(Some(Imp::Epoll(epoll))),
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOSYS) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn new_epoll() -> io::Result<Option<Imp>> {
+    Ok(None)
+}
+
+fn poll_events(readable: bool, writable: bool) -> libc::c_short {
+    let mut events = 0;
+    if readable {
+        events |= libc::POLLIN;
+    }
+    if writable {
+        events |= libc::POLLOUT;
+    }
+    events
+}
+
+/// Converts a timeout to milliseconds, rounding up so that waiting never ends
+/// before the timeout has elapsed.
+fn timeout_ms(timeout: Option<Duration>) -> c_int {
+    match timeout {
+        None => -1,
+        Some(dur) => {
+            let ms = dur.as_secs()
+                        .saturating_mul(1000)
+                        .saturating_add((dur.subsec_nanos() as u64 + 999_999) / 1_000_000);
+            cmp::min(ms, c_int::max_value() as u64) as c_int
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod epoll {
+    use prelude::v1::*;
+
+    use io;
+    use libc::{self, c_int};
+    use sys::{cvt, cvt_r};
+    use sys::fd::FileDesc;
+
+    use super::Event;
+
+    pub struct Epoll(FileDesc);
+
+    impl Epoll {
+        pub fn new() -> io::Result<Epoll> {
+            // As with `pipe2`, `epoll_create1` (which can set CLOEXEC
+            // atomically) was added in 2.6.27, so detect it dynamically.
+            weak! { fn epoll_create1(c_int) -> c_int }
+            if let Some(epoll_create1) = epoll_create1.get() {
+                match cvt(unsafe { epoll_create1(libc::EPOLL_CLOEXEC) }) {
+                    Ok(fd) => return Ok(Epoll(FileDesc::new(fd))),
+                    Err(ref e) if e.raw_os_error() == Some(libc::ENOSYS) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            // The size is ignored, but must be positive.
+            let fd = FileDesc::new(cvt(unsafe { libc::epoll_create(1) })?);
+            fd.set_cloexec();
+            Ok(Epoll(fd))
+        }
+
+        pub fn ctl(&self, op: c_int, fd: c_int, token: usize, readable: bool, writable: bool)
+                   -> io::Result<()> {
+            let mut events = 0;
+            if readable {
+                events |= libc::EPOLLIN;
+            }
+            if writable {
+                events |= libc::EPOLLOUT;
+            }
+            let mut event = libc::epoll_event { events: events as u32, u64: token as u64 };
+            cvt(unsafe { libc::epoll_ctl(self.0.raw(), op, fd, &mut event) })?;
+            Ok(())
+        }
+
+        pub fn wait(&self, events: &mut Vec<Event>, max: usize, timeout: c_int)
+                    -> io::Result<()> {
+            let mut buf: Vec<libc::epoll_event> = Vec::with_capacity(max);
+            let n = cvt_r(|| unsafe {
+                libc::epoll_wait(self.0.raw(), buf.as_mut_ptr(), max as c_int, timeout)
+            })?;
+            unsafe { buf.set_len(n as usize) };
+            events.extend(buf.iter().map(|event| {
+                let flags = event.events as c_int;
+                Event {
+                    token: event.u64 as usize,
+                    readable: flags & libc::EPOLLIN != 0,
+                    writable: flags & libc::EPOLLOUT != 0,
+                    hangup: flags & libc::EPOLLHUP != 0,
+                    error: flags & libc::EPOLLERR != 0,
+                }
+            }));
+            Ok(())
+        }
+    }
+}
@@ -11,6 +11,7 @@
 use prelude::v1::*;
 use os::unix::prelude::*;
 
+use cmp;
 use collections::hash_map::{HashMap, Entry};
 use env;
 use ffi::{OsString, OsStr, CString, CStr};
@@ -23,6 +24,8 @@ use sys::fd::FileDesc;
 use sys::fs::{File, OpenOptions};
 use sys::pipe::{self, AnonPipe};
 use sys::{self, cvt, cvt_r};
+use thread;
+use time::{Duration, Instant};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -55,8 +58,11 @@ pub struct Command {
     cwd: Option<CString>,
     uid: Option<uid_t>,
     gid: Option<gid_t>,
+    pgroup: Option<pid_t>,
     session_leader: bool,
     saw_nul: bool,
+    rlimits: Vec<(c_int, libc::rlim_t, libc::rlim_t)>,
+    fd_mappings: Vec<FdMapping>,
     closures: Vec<Box<FnMut() -> io::Result<()> + Send + Sync>>,
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
@@ -79,6 +85,14 @@ struct ChildPipes {
     stderr: ChildStdio,
 }
 
+// a descriptor to be made available to the child as `child`; `tmp` is where
+// the child moves it to while setting up its descriptors
+struct FdMapping {
+    fd: FileDesc,
+    child: c_int,
+    tmp: c_int,
+}
+
 enum ChildStdio {
     Inherit,
     Explicit(c_int),
@@ -105,8 +119,11 @@ impl Command {
             cwd: None,
             uid: None,
             gid: None,
+            pgroup: None,
             session_leader: false,
             saw_nul: saw_nul,
+            rlimits: Vec::new(),
+            fd_mappings: Vec::new(),
             closures: Vec::new(),
             stdin: None,
             stdout: None,
@@ -197,9 +214,21 @@ impl Command {
     pub fn gid(&mut self, id: gid_t) {
         self.gid = Some(id);
     }
+    pub fn pgroup(&mut self, pgroup: pid_t) {
+        self.pgroup = Some(pgroup);
+    }
     pub fn session_leader(&mut self, session_leader: bool) {
         self.session_leader = session_leader;
     }
+    pub fn rlimit(&mut self, resource: c_int, soft: libc::rlim_t, hard: libc::rlim_t) {
+        self.rlimits.push((resource, soft, hard));
+    }
+    pub fn map_fd(&mut self, fd: FileDesc, child: c_int) {
+        // Only the copy made for the mapping may leak into the child, or
+        // into children spawned by other threads in the meantime.
+        fd.set_cloexec();
+        self.fd_mappings.push(FdMapping { fd: fd, child: child, tmp: -1 });
+    }
 
     pub fn before_exec(&mut self,
                        f: Box<FnMut() -> io::Result<()> + Send + Sync>) {
@@ -232,7 +261,13 @@ impl Command {
             match cvt(libc::fork())? {
                 0 => {
                     drop(input);
-                    let err = self.do_exec(theirs);
+                    // Move the error pipe above the descriptors given to the
+                    // child, which would otherwise overwrite it.
+                    let fd = libc::fcntl(output.fd().raw(), libc::F_DUPFD, self.min_free_fd());
+                    let (output, err) = match cvt(fd) {
+                        Ok(fd) => (sys::pipe::AnonPipe::from_fd(fd), self.do_exec(theirs)),
+                        Err(e) => (output, e),
+                    };
                     let errno = err.raw_os_error().unwrap_or(libc::EINVAL) as u32;
                     let bytes = [
                         (errno >> 24) as u8,
@@ -304,6 +339,14 @@ impl Command {
         }
     }
 
+    /// The lowest descriptor above stdio and all descriptors mapped into the
+    /// child.
+    fn min_free_fd(&self) -> c_int {
+        self.fd_mappings.iter().fold(libc::STDERR_FILENO, |max, mapping| {
+            cmp::max(max, mapping.child)
+        }) + 1
+    }
+
     // And at this point we've reached a special time in the life of the
     // child. The child must now be considered hamstrung and unable to
     // do anything other than syscalls really. Consider the following
@@ -342,6 +385,14 @@ impl Command {
             })
         }
 
+        // Move the descriptors to be mapped above all of their targets
+        // first, so that neither setting up stdio nor the mapping itself
+        // overwrites one before it has been duplicated.
+        let min_tmp = self.min_free_fd();
+        for mapping in self.fd_mappings.iter_mut() {
+            mapping.tmp = t!(cvt(libc::fcntl(mapping.fd.raw(), libc::F_DUPFD, min_tmp)));
+        }
+
         if let Some(fd) = stdio.stdin.fd() {
             t!(cvt_r(|| libc::dup2(fd, libc::STDIN_FILENO)));
         }
@@ -352,6 +403,20 @@ impl Command {
             t!(cvt_r(|| libc::dup2(fd, libc::STDERR_FILENO)));
         }
 
+        // `dup2` clears CLOEXEC on the new descriptor, so the mapped
+        // descriptors are inherited even if the originals are not.
+        for mapping in &self.fd_mappings {
+            t!(cvt_r(|| libc::dup2(mapping.tmp, mapping.child)));
+            libc::close(mapping.tmp);
+        }
+
+        // Resource limits are set before dropping privileges, which may be
+        // needed to raise the hard limits.
+        for &(resource, soft, hard) in &self.rlimits {
+            let limit = libc::rlimit { rlim_cur: soft, rlim_max: hard };
+            t!(cvt(libc::setrlimit(resource, &limit)));
+        }
+
         if let Some(u) = self.gid {
             t!(cvt(libc::setgid(u as gid_t)));
         }
@@ -373,6 +438,9 @@ impl Command {
             // error, but ignore it anyway.
             let _ = libc::setsid();
         }
+        if let Some(pgroup) = self.pgroup {
+            t!(cvt(libc::setpgid(0, pgroup)));
+        }
         if let Some(ref cwd) = self.cwd {
             t!(cvt(libc::chdir(cwd.as_ptr())));
         }
@@ -484,6 +552,18 @@ impl Stdio {
     }
 }
 
+impl From<AnonPipe> for Stdio {
+    fn from(pipe: AnonPipe) -> Stdio {
+        Stdio::Fd(pipe.into_fd())
+    }
+}
+
+impl From<File> for Stdio {
+    fn from(file: File) -> Stdio {
+        Stdio::Fd(file.into_fd())
+    }
+}
+
 impl ChildStdio {
     fn fd(&self) -> Option<c_int> {
         match *self {
@@ -548,6 +628,11 @@ impl ExitStatus {
             None
         }
     }
+
+    pub fn core_dumped(&self) -> bool {
+        // WCOREFLAG, which is the same on all supported platforms.
+        !self.exited() && self.0 & 0x80 != 0
+    }
 }
 
 impl From<c_int> for ExitStatus {
@@ -599,6 +684,183 @@ impl Process {
         self.status = Some(ExitStatus(status));
         Ok(ExitStatus(status))
     }
+
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status))
+        }
+        let mut status = 0 as c_int;
+        let pid = cvt_r(|| unsafe {
+            libc::waitpid(self.pid, &mut status, libc::WNOHANG)
+        })?;
+        if pid == 0 {
+            Ok(None)
+        } else {
+            self.status = Some(ExitStatus(status));
+            Ok(Some(ExitStatus(status)))
+        }
+    }
+
+    pub fn wait_timeout(&mut self, dur: Duration) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.try_wait()? {
+            return Ok(Some(status))
+        }
+        // Time is measured from the start rather than up to a deadline, as
+        // `dur` may be too long for `Instant` to represent the deadline.
+        let start = Instant::now();
+        match sigchld::waiters() {
+            Some(waiters) => waiters.wait(self, start, dur),
+            None => self.poll_for(start, dur),
+        }
+    }
+
+    // Used when SIGCHLD can't be caught: polls with increasing intervals.
+    fn poll_for(&mut self, start: Instant, dur: Duration) -> io::Result<Option<ExitStatus>> {
+        let mut interval = Duration::from_millis(1);
+        loop {
+            if let Some(status) = self.try_wait()? {
+                return Ok(Some(status))
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= dur {
+                return Ok(None)
+            }
+            thread::sleep(cmp::min(interval, dur - elapsed));
+            interval = cmp::min(interval * 2, Duration::from_millis(50));
+        }
+    }
+}
+
+// Timed waits for children are woken up by SIGCHLD. The signal handler (which
+// chains to any handler installed before it) writes to a self-pipe, and a
+// helper thread reading the other end wakes up every waiting thread, each of
+// which then checks its own child with `waitpid(WNOHANG)`. Waiters hold the
+// lock from checking their child until they sleep, and the helper takes it to
+// wake them up, so a SIGCHLD can't be missed in between.
+mod sigchld {
+    use prelude::v1::*;
+
+    use cmp;
+    use io::{self, ErrorKind};
+    use libc::{self, c_int, c_void};
+    use mem;
+    use ptr;
+    use sync::{Condvar, Mutex, Once};
+    use sys::os;
+    use sys::pipe::anon_pipe;
+    use thread;
+    use time::{Duration, Instant};
+
+    use super::{ExitStatus, Process};
+
+    pub struct Waiters {
+        lock: Mutex<()>,
+        cvar: Condvar,
+    }
+
+    static INIT: Once = Once::new();
+    static mut WAITERS: *const Waiters = 0 as *const Waiters;
+    static mut WRITE_FD: c_int = -1;
+    static mut PREVIOUS: *const libc::sigaction = 0 as *const libc::sigaction;
+
+    /// Returns the threads waiting for SIGCHLD, installing the handler on
+    /// first use, or `None` if SIGCHLD is ignored (in which case children
+    /// are reaped automatically) or the handler could not be set up.
+    pub fn waiters() -> Option<&'static Waiters> {
+        INIT.call_once(|| unsafe {
+            if let Ok(waiters) = install() {
+                WAITERS = waiters;
+            }
+        });
+        unsafe { WAITERS.as_ref() }
+    }
+
+    unsafe fn install() -> io::Result<*const Waiters> {
+        let mut previous: libc::sigaction = mem::zeroed();
+        if libc::sigaction(libc::SIGCHLD, ptr::null(), &mut previous) != 0 {
+            return Err(io::Error::last_os_error())
+        }
+        if previous.sa_sigaction == libc::SIG_IGN {
+            return Err(io::Error::new(ErrorKind::Other, "SIGCHLD is ignored"))
+        }
+
+        let (reader, writer) = anon_pipe()?;
+        // The handler must never block.
+        writer.fd().set_nonblocking(true);
+        let waiters: &'static Waiters = &*Box::into_raw(Box::new(Waiters {
+            lock: Mutex::new(()),
+            cvar: Condvar::new(),
+        }));
+        thread::Builder::new().name("sigchld".to_string()).spawn(move || {
+            let mut buf = [0; 64];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let _lock = waiters.lock.lock().unwrap();
+                        waiters.cvar.notify_all();
+                    }
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(_) => break,
+                }
+            }
+        })?;
+
+        PREVIOUS = Box::into_raw(Box::new(previous));
+        WRITE_FD = writer.into_fd().into_raw();
+        // Keep the signals blocked by, and the SIGCHLD behaviour asked for by,
+        // the handler we replace.
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_mask = previous.sa_mask;
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART |
+            previous.sa_flags & (libc::SA_NOCLDSTOP | libc::SA_NOCLDWAIT);
+        action.sa_sigaction = handler as libc::sighandler_t;
+        if libc::sigaction(libc::SIGCHLD, &action, ptr::null_mut()) != 0 {
+            return Err(io::Error::last_os_error())
+        }
+        Ok(waiters as *const Waiters)
+    }
+
+    unsafe extern fn handler(signum: c_int, info: *mut libc::siginfo_t, data: *mut c_void) {
+        // The write may fail if the pipe is full, and the interrupted code
+        // must not see its `errno` change.
+        let errno = os::errno();
+        libc::write(WRITE_FD, b"x".as_ptr() as *const c_void, 1);
+        os::set_errno(errno);
+
+        let previous = &*PREVIOUS;
+        if previous.sa_sigaction == libc::SIG_DFL || previous.sa_sigaction == libc::SIG_IGN {
+            return
+        }
+        if previous.sa_flags & libc::SA_SIGINFO != 0 {
+            let f: unsafe extern fn(c_int, *mut libc::siginfo_t, *mut c_void) =
+                mem::transmute(previous.sa_sigaction);
+            f(signum, info, data);
+        } else {
+            let f: unsafe extern fn(c_int) = mem::transmute(previous.sa_sigaction);
+            f(signum);
+        }
+    }
+
+    impl Waiters {
+        pub fn wait(&self, process: &mut Process, start: Instant, dur: Duration)
+                    -> io::Result<Option<ExitStatus>> {
+            let mut lock = self.lock.lock().unwrap();
+            loop {
+                if let Some(status) = process.try_wait()? {
+                    return Ok(Some(status))
+                }
+                let elapsed = start.elapsed();
+                if elapsed >= dur {
+                    return Ok(None)
+                }
+                // Someone may replace our handler behind our back, so don't
+                // rely on being woken up.
+                let timeout = cmp::min(dur - elapsed, Duration::from_millis(50));
+                lock = self.cvar.wait_timeout(lock, timeout).unwrap().0;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +163,44 @@ impl Drop for Thread {
     }
 }
 
+/// Returns the number of CPUs the current thread may run on.
+pub fn available_cpus() -> usize {
+    sched_cpus().unwrap_or_else(|| {
+        let cpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) };
+        cmp::max(cpus, 1) as usize
+    })
+}
+
+/// Counts the CPUs in the affinity mask of the current thread, which may be
+/// fewer than are online (for example under `taskset` or in a cgroup).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn sched_cpus() -> Option<usize> {
+    // Looked up dynamically as not every libc we link against has it.
+    weak!(fn sched_getaffinity(libc::pid_t, libc::size_t, *mut u64) -> libc::c_int);
+
+    let sched_getaffinity = match sched_getaffinity.get() {
+        Some(f) => f,
+        None => return None,
+    };
+    // Room for 1024 CPUs, the size of glibc's `cpu_set_t`.
+    let mut set = [0u64; 16];
+    let ret = unsafe {
+        sched_getaffinity(0, mem::size_of_val(&set) as libc::size_t, set.as_mut_ptr())
+    };
+    if ret != 0 {
+        return None;
+    }
+    match set.iter().fold(0, |cpus, word| cpus + word.count_ones() as usize) {
+        0 => None,
+        cpus => Some(cpus),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn sched_cpus() -> Option<usize> {
+    None
+}
+
 #[cfg(all(not(all(target_os = "linux", not(target_env = "musl"))),
           not(target_os = "freebsd"),
           not(target_os = "macos"),
@@ -26,6 +26,8 @@ use sys_common::FromInner;
 
 use super::to_u16s;
 
+pub use sys_common::fs_watch::Watcher;
+
 pub struct File { handle: Handle }
 
 #[derive(Clone)]
@@ -271,6 +273,37 @@ impl File {
 
     pub fn datasync(&self) -> io::Result<()> { self.fsync() }
 
+    pub fn lock(&self, exclusive: bool, block: bool) -> io::Result<()> {
+        let mut flags = 0;
+        if exclusive {
+            flags |= c::LOCKFILE_EXCLUSIVE_LOCK;
+        }
+        if !block {
+            flags |= c::LOCKFILE_FAIL_IMMEDIATELY;
+        }
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        match cvt(unsafe {
+            c::LockFileEx(self.handle.raw(), flags, 0, !0, !0, &mut overlapped)
+        }) {
+            Ok(_) => Ok(()),
+            // This is also the error of reading or writing a locked region,
+            // so it is only a would-block condition here.
+            Err(ref e) if e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => {
+                Err(io::Error::new(io::ErrorKind::WouldBlock,
+                                   "the file is locked by another handle"))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            c::UnlockFileEx(self.handle.raw(), 0, !0, !0, &mut overlapped)
+        })?;
+        Ok(())
+    }
+
     pub fn truncate(&self, size: u64) -> io::Result<()> {
         let mut info = c::FILE_END_OF_FILE_INFO {
             EndOfFile: size as c::LARGE_INTEGER,
@@ -616,6 +649,17 @@ pub fn symlink_inner(src: &Path, dst: &Path, dir: bool) -> io::Result<()> {
     Ok(())
 }
 
+pub fn sync_dir(_p: &Path) -> io::Result<()> {
+    // Directories can't be opened for flushing on Windows, and a rename is
+    // made durable along with the file system metadata.
+    Ok(())
+}
+
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let dir = lstat(from)?.file_type().is_symlink_dir();
+    symlink_inner(&readlink(from)?, to, dir)
+}
+
 pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
     let src = to_u16s(src)?;
     let dst = to_u16s(dst)?;
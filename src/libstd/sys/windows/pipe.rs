@@ -20,6 +20,7 @@ use slice;
 use sys::c;
 use sys::fs::{File, OpenOptions};
 use sys::handle::Handle;
+use thread;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Anonymous pipes
@@ -156,6 +157,45 @@ pub fn read2(p1: AnonPipe,
     }
 }
 
+/// Writes `input` to `stdin` (closing it afterwards) while reading `stdout`
+/// and `stderr` to their ends.
+///
+/// Only reads are done with overlapped I/O, so the input is written by a
+/// helper thread.
+pub fn communicate(stdin: Option<AnonPipe>,
+                   input: &[u8],
+                   stdout: Option<AnonPipe>,
+                   out: &mut Vec<u8>,
+                   stderr: Option<AnonPipe>,
+                   err: &mut Vec<u8>) -> io::Result<()> {
+    let stdin = if input.is_empty() { None } else { stdin };
+    thread::scope(|s| {
+        let writer = stdin.map(|stdin| s.spawn(move || write_input(stdin, input)));
+        let read = match (stdout, stderr) {
+            (Some(p1), Some(p2)) => read2(p1, out, p2, err),
+            (Some(p), None) => p.read_to_end(out).map(|_| ()),
+            (None, Some(p)) => p.read_to_end(err).map(|_| ()),
+            (None, None) => Ok(()),
+        };
+        let written = writer.map_or(Ok(()), |writer| writer.join().unwrap());
+        read.and(written)
+    })
+}
+
+// If the reader of `stdin` goes away before all of `input` is written, the
+// rest is silently dropped.
+fn write_input(stdin: AnonPipe, mut input: &[u8]) -> io::Result<()> {
+    while !input.is_empty() {
+        match stdin.write(input) {
+            Ok(n) => input = &input[n..],
+            Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe ||
+                          e.raw_os_error() == Some(c::ERROR_NO_DATA as i32) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
 struct AsyncPipe<'a> {
     pipe: Handle,
     event: Handle,
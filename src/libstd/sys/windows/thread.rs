@@ -11,6 +11,7 @@
 use prelude::v1::*;
 
 use alloc::boxed::FnBox;
+use cmp;
 use io;
 use ffi::CStr;
 use mem;
@@ -84,6 +85,15 @@ impl Thread {
     pub fn into_handle(self) -> Handle { self.handle }
 }
 
+/// Returns the number of processors in the system.
+pub fn available_cpus() -> usize {
+    unsafe {
+        let mut info: c::SYSTEM_INFO = mem::zeroed();
+        c::GetSystemInfo(&mut info);
+        cmp::max(info.dwNumberOfProcessors as usize, 1)
+    }
+}
+
 #[cfg_attr(test, allow(dead_code))]
 pub mod guard {
     pub unsafe fn current() -> Option<usize> { None }
@@ -72,6 +72,33 @@
 //! value produced by the child thread, or `Err` of the value given to
 //! a call to `panic!` if the child panicked.
 //!
+//! ## Scoped threads
+//!
+//! Threads spawned with `thread::spawn` may outlive the function that spawned
+//! them, so they can only borrow data which lives forever. The
+//! `thread::scope` function instead provides a scope in which threads can be
+//! spawned that borrow from the enclosing stack frame; they are all joined
+//! before `scope` returns, and a panic in one of them is propagated to the
+//! caller:
+//!
+//! ```rust
+//! #![feature(scoped_threads)]
+//! use std::thread;
+//!
+//! let greeting = String::from("hello");
+//! thread::scope(|s| {
+//!     s.spawn(|| println!("{} from a scoped thread", greeting));
+//! });
+//! ```
+//!
+//! ## Thread pools
+//!
+//! For data parallelism, `thread::join` runs two closures in parallel on the
+//! worker threads of a work-stealing `ThreadPool`, and the pool provides
+//! parallel `for_each`, `map` and `reduce` operations on slices built on it.
+//! Unless called from the workers of another pool, `join` uses a global pool
+//! with a thread for each available CPU.
+//!
 //! ## Configuring threads
 //!
 //! A new thread can be configured before it is spawned via the `Builder` type,
@@ -203,6 +230,24 @@ pub use self::scoped_tls::ScopedKey;
 #[unstable(feature = "libstd_thread_internals", issue = "0")]
 #[doc(hidden)] pub use self::scoped_tls::__KeyInner as __ScopedKeyInner;
 
+////////////////////////////////////////////////////////////////////////////////
+// Scoped threads
+////////////////////////////////////////////////////////////////////////////////
+
+mod scoped;
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub use self::scoped::{scope, Scope, ScopedJoinHandle};
+
+////////////////////////////////////////////////////////////////////////////////
+// Thread pools
+////////////////////////////////////////////////////////////////////////////////
+
+mod pool;
+
+#[unstable(feature = "thread_pool", issue = "0")]
+pub use self::pool::{join, ThreadPool};
+
 ////////////////////////////////////////////////////////////////////////////////
 // Builder
 ////////////////////////////////////////////////////////////////////////////////
@@ -258,6 +303,16 @@ impl Builder {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>> where
         F: FnOnce() -> T, F: Send + 'static, T: Send + 'static
+    {
+        unsafe { self.spawn_unchecked(f).map(JoinHandle) }
+    }
+
+    /// Spawns a new thread running a closure which need not be `'static`.
+    ///
+    /// The caller must join the thread before anything borrowed by `F` or
+    /// `T` goes away, as scoped threads do.
+    unsafe fn spawn_unchecked<'a, F, T>(self, f: F) -> io::Result<JoinInner<T>> where
+        F: FnOnce() -> T, F: Send + 'a, T: Send + 'a
     {
         let Builder { name, stack_size } = self;
 
@@ -274,20 +329,16 @@ impl Builder {
             if let Some(name) = their_thread.cname() {
                 imp::Thread::set_name(name);
             }
-            unsafe {
-                thread_info::set(imp::guard::current(), their_thread);
-                let try_result = panic::catch_unwind(panic::AssertUnwindSafe(f));
-                *their_packet.get() = Some(try_result);
-            }
+            thread_info::set(imp::guard::current(), their_thread);
+            let try_result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+            *their_packet.get() = Some(try_result);
         };
 
-        Ok(JoinHandle(JoinInner {
-            native: unsafe {
-                Some(imp::Thread::new(stack_size, Box::new(main))?)
-            },
+        Ok(JoinInner {
+            native: Some(imp::Thread::new(stack_size, Box::new(main))?),
             thread: my_thread,
             packet: Packet(my_packet),
-        }))
+        })
     }
 }
 
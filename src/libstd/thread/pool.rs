@@ -0,0 +1,663 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A work-stealing thread pool.
+//!
+//! Every worker thread owns a deque of jobs. The second closure of a `join`
+//! is pushed onto the back of the deque of the worker calling `join`, and
+//! unless another worker stole it in the meantime, it is popped back off and
+//! called directly once the first closure returns, so that in the common
+//! case `join` costs little more than calling both closures. Idle workers
+//! steal from the front of the other deques, where the oldest (and so
+//! usually the largest) jobs are, and from a queue of jobs injected by
+//! threads outside of the pool.
+//!
+//! Jobs are referred to by raw pointers into the stack frame which created
+//! them. This is sound because that frame does not return before the job has
+//! either run or been taken back off the deque.
+
+use prelude::v1::*;
+
+use cell::{Cell, UnsafeCell};
+use collections::VecDeque;
+use fmt;
+use io;
+use panic::{self, AssertUnwindSafe};
+use ptr;
+use sync::{Arc, Condvar, Mutex, Once};
+use sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use sys::thread as imp;
+
+use super::{Builder, Result};
+
+/// A pool of worker threads which run the closures given to `join` in
+/// parallel.
+///
+/// The parallel operations on slices divide the slice in halves with `join`
+/// until there are a few more pieces than there are workers, so that a
+/// worker which finishes early can steal some of the remaining work.
+///
+/// Dropping the pool lets its workers exit once they run out of work.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(thread_pool)]
+/// use std::thread::ThreadPool;
+///
+/// let pool = ThreadPool::with_threads(4).unwrap();
+/// let squares = pool.map(&[1, 2, 3, 4], |n| n * n);
+/// assert_eq!(squares, [1, 4, 9, 16]);
+/// assert_eq!(pool.reduce(&squares, || 0, |a, b| a + b), 30);
+/// ```
+#[unstable(feature = "thread_pool", issue = "0")]
+pub struct ThreadPool {
+    registry: Arc<Registry>,
+}
+
+impl ThreadPool {
+    /// Creates a pool with a worker thread for each CPU the current thread
+    /// may run on.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn new() -> io::Result<ThreadPool> {
+        ThreadPool::with_threads(imp::available_cpus())
+    }
+
+    /// Creates a pool with `threads` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn with_threads(threads: usize) -> io::Result<ThreadPool> {
+        assert!(threads > 0, "a thread pool needs at least one thread");
+        Registry::new(threads).map(|registry| ThreadPool { registry: registry })
+    }
+
+    /// Returns the pool used by the `join` function when it is called from a
+    /// thread outside of any pool, creating it on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool does not exist yet and its threads cannot be
+    /// created.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn global() -> &'static ThreadPool {
+        static INIT: Once = Once::new();
+        static mut GLOBAL: *const ThreadPool = 0 as *const ThreadPool;
+        unsafe {
+            INIT.call_once(|| {
+                let pool = ThreadPool::new().expect("failed to create the global thread pool");
+                GLOBAL = Box::into_raw(Box::new(pool));
+            });
+            &*GLOBAL
+        }
+    }
+
+    /// Returns the number of worker threads in the pool.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn num_threads(&self) -> usize {
+        self.registry.deques.len()
+    }
+
+    /// Calls `f` on one of the worker threads of the pool, and returns its
+    /// result once it is done. Calls to `join` in `f` use this pool.
+    ///
+    /// If `f` panics, the panic is propagated to the caller.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn install<F, R>(&self, f: F) -> R
+        where F: FnOnce() -> R + Send, R: Send
+    {
+        unsafe {
+            if let Some(worker) = Worker::current() {
+                if &*worker.registry as *const Registry == &*self.registry as *const Registry {
+                    return f();
+                }
+            }
+            let job = StackJob::new(f, LockLatch::new());
+            self.registry.inject(job.as_job_ref());
+            job.latch.wait();
+            job.into_result()
+        }
+    }
+
+    /// Calls `a` and `b`, possibly in parallel, on the worker threads of the
+    /// pool, and returns both results.
+    ///
+    /// See the `join` function.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+        where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+    {
+        self.install(move || join(a, b))
+    }
+
+    /// Calls `f` on every element of `slice` in parallel.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn for_each<T, F>(&self, slice: &[T], f: F)
+        where T: Sync, F: Fn(&T) + Sync
+    {
+        let splits = self.splits();
+        self.install(|| split_for_each(slice, &f, splits))
+    }
+
+    /// Calls `f` on every element of `slice` in parallel, and returns the
+    /// results in the order of the elements.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn map<T, U, F>(&self, slice: &[T], f: F) -> Vec<U>
+        where T: Sync, U: Send, F: Fn(&T) -> U + Sync
+    {
+        let splits = self.splits();
+        self.install(|| split_map(slice, &f, splits))
+    }
+
+    /// Combines the elements of `slice` with `op` in parallel.
+    ///
+    /// Each piece of the slice is folded starting with a value returned by
+    /// `identity`, and the results are then combined with `op`, so `op` must
+    /// be associative and `identity()` must leave values unchanged when
+    /// combined with them. An empty slice reduces to `identity()`.
+    #[unstable(feature = "thread_pool", issue = "0")]
+    pub fn reduce<T, ID, F>(&self, slice: &[T], identity: ID, op: F) -> T
+        where T: Clone + Send + Sync, ID: Fn() -> T + Sync, F: Fn(T, T) -> T + Sync
+    {
+        let splits = self.splits();
+        self.install(|| split_reduce(slice, &identity, &op, splits))
+    }
+
+    /// The number of times a slice is divided before the pieces are
+    /// processed sequentially.
+    fn splits(&self) -> usize {
+        self.num_threads() * 4
+    }
+}
+
+#[unstable(feature = "thread_pool", issue = "0")]
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.registry.terminate();
+    }
+}
+
+#[unstable(feature = "thread_pool", issue = "0")]
+impl fmt::Debug for ThreadPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThreadPool").field("threads", &self.num_threads()).finish()
+    }
+}
+
+/// Calls `a` and `b`, possibly in parallel, and returns both results.
+///
+/// On a worker thread of a `ThreadPool`, `b` is made available for the
+/// other workers of the pool to steal while the current thread calls `a`.
+/// Elsewhere, both closures are run by the global pool.
+///
+/// # Panics
+///
+/// If either closure panics, the panic is propagated once both have
+/// returned (or `b` is known not to have started); if both panic, the panic
+/// of `a` is propagated.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(thread_pool)]
+/// use std::thread;
+///
+/// fn sum(numbers: &[u64]) -> u64 {
+///     if numbers.len() <= 1024 {
+///         return numbers.iter().fold(0, |a, b| a + b);
+///     }
+///     let (left, right) = numbers.split_at(numbers.len() / 2);
+///     let (left, right) = thread::join(|| sum(left), || sum(right));
+///     left + right
+/// }
+///
+/// let numbers: Vec<u64> = (0..100000).collect();
+/// assert_eq!(sum(&numbers), 4999950000);
+/// ```
+#[unstable(feature = "thread_pool", issue = "0")]
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+    where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+{
+    unsafe {
+        match Worker::current() {
+            Some(worker) => join_in_worker(worker, a, b),
+            None => ThreadPool::global().install(move || join(a, b)),
+        }
+    }
+}
+
+unsafe fn join_in_worker<A, B, RA, RB>(worker: &Worker, a: A, b: B) -> (RA, RB)
+    where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+{
+    let job_b = StackJob::new(b, SpinLatch::new());
+    let job_b_ref = job_b.as_job_ref();
+    worker.push(job_b_ref);
+
+    // Whatever happens to `a`, `b` must not be left on the deque (or running)
+    // once this frame is gone.
+    let result_a = panic::catch_unwind(AssertUnwindSafe(a));
+    let stolen = !worker.take_local(job_b_ref);
+    if stolen {
+        worker.wait_until(&job_b.latch);
+    }
+    let result_a = match result_a {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    };
+    let result_b = if stolen { job_b.into_result() } else { job_b.run_inline() };
+    (result_a, result_b)
+}
+
+fn split_for_each<T, F>(slice: &[T], f: &F, splits: usize)
+    where T: Sync, F: Fn(&T) + Sync
+{
+    if splits == 0 || slice.len() < 2 {
+        for item in slice {
+            f(item);
+        }
+    } else {
+        let (left, right) = slice.split_at(slice.len() / 2);
+        join(|| split_for_each(left, f, splits / 2),
+             || split_for_each(right, f, splits / 2));
+    }
+}
+
+fn split_map<T, U, F>(slice: &[T], f: &F, splits: usize) -> Vec<U>
+    where T: Sync, U: Send, F: Fn(&T) -> U + Sync
+{
+    if splits == 0 || slice.len() < 2 {
+        slice.iter().map(f).collect()
+    } else {
+        let (left, right) = slice.split_at(slice.len() / 2);
+        let (mut left, right) = join(|| split_map(left, f, splits / 2),
+                                     || split_map(right, f, splits / 2));
+        left.extend(right);
+        left
+    }
+}
+
+fn split_reduce<T, ID, F>(slice: &[T], identity: &ID, op: &F, splits: usize) -> T
+    where T: Clone + Send + Sync, ID: Fn() -> T + Sync, F: Fn(T, T) -> T + Sync
+{
+    if splits == 0 || slice.len() < 2 {
+        slice.iter().cloned().fold(identity(), |a, b| op(a, b))
+    } else {
+        let (left, right) = slice.split_at(slice.len() / 2);
+        let (left, right) = join(|| split_reduce(left, identity, op, splits / 2),
+                                 || split_reduce(right, identity, op, splits / 2));
+        op(left, right)
+    }
+}
+
+/// The state shared by the workers of a pool.
+struct Registry {
+    /// The deque of each worker, by index.
+    deques: Vec<Mutex<VecDeque<JobRef>>>,
+    /// Jobs injected from outside of the pool.
+    injected: Mutex<VecDeque<JobRef>>,
+    /// The number of workers which are going to sleep or asleep, so that
+    /// new jobs only take `terminated` to wake a worker if there is one.
+    sleeping: AtomicUsize,
+    /// Whether the pool has been dropped. Idle workers wait on `wakeup`
+    /// with this lock held.
+    terminated: Mutex<bool>,
+    wakeup: Condvar,
+}
+
+impl Registry {
+    fn new(threads: usize) -> io::Result<Arc<Registry>> {
+        let registry = Arc::new(Registry {
+            deques: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+            injected: Mutex::new(VecDeque::new()),
+            sleeping: AtomicUsize::new(0),
+            terminated: Mutex::new(false),
+            wakeup: Condvar::new(),
+        });
+        for index in 0..threads {
+            let worker = Worker { registry: registry.clone(), index: index };
+            let spawned = Builder::new().name(format!("pool worker {}", index))
+                                        .spawn(move || worker.run());
+            if let Err(e) = spawned {
+                registry.terminate();
+                return Err(e);
+            }
+        }
+        Ok(registry)
+    }
+
+    fn inject(&self, job: JobRef) {
+        self.injected.lock().unwrap().push_back(job);
+        self.notify();
+    }
+
+    /// Wakes up a sleeping worker, if there is one, to look for work.
+    fn notify(&self) {
+        if self.sleeping.load(Ordering::SeqCst) > 0 {
+            let _terminated = self.terminated.lock().unwrap();
+            self.wakeup.notify_one();
+        }
+    }
+
+    fn terminate(&self) {
+        *self.terminated.lock().unwrap() = true;
+        self.wakeup.notify_all();
+    }
+
+    /// Takes the oldest job from the deque of a worker other than `thief`,
+    /// or from the injected jobs.
+    fn steal(&self, thief: usize) -> Option<JobRef> {
+        let workers = self.deques.len();
+        for i in 1..workers {
+            let victim = (thief + i) % workers;
+            if let Some(job) = self.deques[victim].lock().unwrap().pop_front() {
+                return Some(job);
+            }
+        }
+        self.injected.lock().unwrap().pop_front()
+    }
+
+    fn has_work(&self) -> bool {
+        !self.injected.lock().unwrap().is_empty() ||
+            self.deques.iter().any(|deque| !deque.lock().unwrap().is_empty())
+    }
+}
+
+thread_local! {
+    static WORKER: Cell<*const Worker> = Cell::new(ptr::null())
+}
+
+/// A worker thread of a pool. It lives on the stack of its thread, and is
+/// pointed to by `WORKER` while it runs.
+struct Worker {
+    registry: Arc<Registry>,
+    index: usize,
+}
+
+impl Worker {
+    /// Returns the worker running on the current thread, if any.
+    unsafe fn current<'a>() -> Option<&'a Worker> {
+        WORKER.with(|worker| worker.get().as_ref())
+    }
+
+    fn run(self) {
+        WORKER.with(|worker| worker.set(&self));
+        loop {
+            match self.find_work() {
+                Some(job) => unsafe { job.execute() },
+                None => if !self.sleep() { break },
+            }
+        }
+        WORKER.with(|worker| worker.set(ptr::null()));
+    }
+
+    fn deque(&self) -> &Mutex<VecDeque<JobRef>> {
+        &self.registry.deques[self.index]
+    }
+
+    fn push(&self, job: JobRef) {
+        self.deque().lock().unwrap().push_back(job);
+        self.registry.notify();
+    }
+
+    /// Pops `job` off the deque if it is the newest job on it.
+    fn take_local(&self, job: JobRef) -> bool {
+        let mut deque = self.deque().lock().unwrap();
+        if deque.back() == Some(&job) {
+            deque.pop_back();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn find_work(&self) -> Option<JobRef> {
+        let job = self.deque().lock().unwrap().pop_back();
+        job.or_else(|| self.registry.steal(self.index))
+    }
+
+    /// Blocks until there is work to do, returning `false` instead if the
+    /// pool has been dropped and there is none left.
+    fn sleep(&self) -> bool {
+        let mut terminated = self.registry.terminated.lock().unwrap();
+        self.registry.sleeping.fetch_add(1, Ordering::SeqCst);
+        while !*terminated && !self.registry.has_work() {
+            terminated = self.registry.wakeup.wait(terminated).unwrap();
+        }
+        self.registry.sleeping.fetch_sub(1, Ordering::SeqCst);
+        !*terminated || self.registry.has_work()
+    }
+
+    /// Runs other jobs until `latch` is set. As the job setting the latch may
+    /// be running on another thread, this yields rather than sleeps when
+    /// there is nothing to do.
+    fn wait_until(&self, latch: &SpinLatch) {
+        while !latch.probe() {
+            match self.find_work() {
+                Some(job) => unsafe { job.execute() },
+                None => imp::Thread::yield_now(),
+            }
+        }
+    }
+}
+
+/// A type-erased pointer to a job.
+#[derive(Copy)]
+struct JobRef {
+    pointer: *const (),
+    execute_fn: unsafe fn(*const ()),
+}
+
+impl Clone for JobRef {
+    fn clone(&self) -> JobRef {
+        *self
+    }
+}
+
+unsafe impl Send for JobRef {}
+
+impl JobRef {
+    unsafe fn execute(self) {
+        (self.execute_fn)(self.pointer)
+    }
+}
+
+impl PartialEq for JobRef {
+    fn eq(&self, other: &JobRef) -> bool {
+        self.pointer == other.pointer
+    }
+}
+
+/// A job in the stack frame of the thread which is going to wait for it.
+struct StackJob<L, F, R> {
+    latch: L,
+    func: UnsafeCell<Option<F>>,
+    result: UnsafeCell<Option<Result<R>>>,
+}
+
+impl<L: Latch, F: FnOnce() -> R, R> StackJob<L, F, R> {
+    fn new(func: F, latch: L) -> StackJob<L, F, R> {
+        StackJob {
+            latch: latch,
+            func: UnsafeCell::new(Some(func)),
+            result: UnsafeCell::new(None),
+        }
+    }
+
+    /// The job must not move or go away before it has run, or been taken
+    /// off the deque it was pushed onto.
+    unsafe fn as_job_ref(&self) -> JobRef {
+        JobRef {
+            pointer: self as *const StackJob<L, F, R> as *const (),
+            execute_fn: StackJob::<L, F, R>::execute,
+        }
+    }
+
+    unsafe fn execute(this: *const ()) {
+        let this = &*(this as *const StackJob<L, F, R>);
+        let func = (*this.func.get()).take().unwrap();
+        *this.result.get() = Some(panic::catch_unwind(AssertUnwindSafe(func)));
+        // The owner of the job may free it as soon as the latch is set.
+        this.latch.set();
+    }
+
+    /// Calls the closure on the current thread, for a job which no other
+    /// thread has taken.
+    unsafe fn run_inline(self) -> R {
+        self.func.into_inner().unwrap()()
+    }
+
+    /// Returns the result of a job which has run, resuming its panic if it
+    /// panicked.
+    unsafe fn into_result(self) -> R {
+        match self.result.into_inner().unwrap() {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+trait Latch {
+    fn set(&self);
+}
+
+/// A latch for a worker, which keeps running jobs while it waits.
+struct SpinLatch(AtomicBool);
+
+impl SpinLatch {
+    fn new() -> SpinLatch {
+        SpinLatch(AtomicBool::new(false))
+    }
+
+    fn probe(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Latch for SpinLatch {
+    fn set(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A latch for a thread outside of the pool, which blocks while it waits.
+struct LockLatch {
+    done: Mutex<bool>,
+    cvar: Condvar,
+}
+
+impl LockLatch {
+    fn new() -> LockLatch {
+        LockLatch { done: Mutex::new(false), cvar: Condvar::new() }
+    }
+
+    fn wait(&self) {
+        let mut done = self.done.lock().unwrap();
+        while !*done {
+            done = self.cvar.wait(done).unwrap();
+        }
+    }
+}
+
+impl Latch for LockLatch {
+    fn set(&self) {
+        let mut done = self.done.lock().unwrap();
+        *done = true;
+        self.cvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::v1::*;
+
+    use panic;
+    use sync::atomic::{AtomicUsize, Ordering};
+    use thread::{self, ThreadPool};
+
+    fn sum(numbers: &[u64]) -> u64 {
+        if numbers.len() <= 16 {
+            return numbers.iter().fold(0, |a, b| a + b);
+        }
+        let (left, right) = numbers.split_at(numbers.len() / 2);
+        let (left, right) = thread::join(|| sum(left), || sum(right));
+        left + right
+    }
+
+    #[test]
+    fn join_outside_pool() {
+        let (a, b) = thread::join(|| 1, || "two");
+        assert_eq!(a, 1);
+        assert_eq!(b, "two");
+    }
+
+    #[test]
+    fn nested_join() {
+        let numbers: Vec<u64> = (0..10000).collect();
+        assert_eq!(sum(&numbers), 49995000);
+        let pool = ThreadPool::with_threads(3).unwrap();
+        assert_eq!(pool.install(|| sum(&numbers)), 49995000);
+    }
+
+    #[test]
+    fn single_thread() {
+        let pool = ThreadPool::with_threads(1).unwrap();
+        let numbers: Vec<u64> = (0..1000).collect();
+        assert_eq!(pool.install(|| sum(&numbers)), 499500);
+    }
+
+    #[test]
+    fn install_runs_on_worker() {
+        let pool = ThreadPool::with_threads(2).unwrap();
+        let name = pool.install(|| thread::current().name().map(|name| name.to_string()));
+        assert!(name.unwrap().starts_with("pool worker"));
+    }
+
+    #[test]
+    fn slices() {
+        let pool = ThreadPool::with_threads(4).unwrap();
+        let numbers: Vec<usize> = (0..1000).collect();
+
+        let count = AtomicUsize::new(0);
+        pool.for_each(&numbers, |&n| { count.fetch_add(n, Ordering::SeqCst); });
+        assert_eq!(count.load(Ordering::SeqCst), 499500);
+
+        let doubled = pool.map(&numbers, |&n| n * 2);
+        assert_eq!(doubled, numbers.iter().map(|&n| n * 2).collect::<Vec<_>>());
+
+        assert_eq!(pool.reduce(&numbers, || 0, |a, b| a + b), 499500);
+        assert_eq!(pool.reduce(&[], || 7, |a: usize, b| a + b), 7);
+        assert!(pool.map(&[] as &[usize], |&n| n).is_empty());
+    }
+
+    #[test]
+    fn panics_propagate() {
+        let pool = ThreadPool::with_threads(2).unwrap();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            pool.join(|| (), || panic!("b"))
+        }));
+        let payload = result.unwrap_err();
+        assert_eq!(*payload.downcast_ref::<&'static str>().unwrap(), "b");
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            pool.for_each(&[1, 2, 3, 4], |&n| if n == 3 { panic!("three") })
+        }));
+        assert!(result.is_err());
+
+        // The pool still works afterwards.
+        assert_eq!(pool.join(|| 1, || 2), (1, 2));
+    }
+
+    #[test]
+    fn available_cpus() {
+        assert!(ThreadPool::global().num_threads() >= 1);
+    }
+}
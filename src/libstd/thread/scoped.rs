@@ -0,0 +1,290 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scoped threads, which may borrow from the stack of the thread that
+//! spawns them.
+//!
+//! Each thread spawned in a scope is registered with the `Scope`, and all of
+//! them are joined when the scope ends. The closure given to `scope` only
+//! ever sees a borrowed `Scope`, so neither the scope nor the handles of its
+//! threads can escape it.
+
+use prelude::v1::*;
+
+use any::Any;
+use fmt;
+use io;
+use marker::PhantomData;
+use mem;
+use panic;
+use sync::{Arc, Mutex};
+
+use super::{Builder, JoinInner, Result, Thread};
+
+/// Creates a scope in which threads can borrow non-`'static` data.
+///
+/// `f` is called with a `Scope`, whose `spawn` method is like the `spawn`
+/// free function except that the closure only needs to outlive the call to
+/// `scope`. Every thread spawned in the scope has been joined by the time
+/// `scope` returns, whether or not it was joined through its handle, so the
+/// data it borrows cannot go away while it is running.
+///
+/// # Panics
+///
+/// If `f` panics, the threads are joined and the panic is then resumed.
+/// Otherwise, if a thread which was not joined through its handle panicked,
+/// `scope` panics with the payload of the first such thread to have been
+/// spawned. The panics of threads joined through their handles are returned
+/// by `join` as usual.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(scoped_threads)]
+/// use std::thread;
+///
+/// let mut numbers = vec![1, 2, 3, 4];
+/// thread::scope(|s| {
+///     for chunk in numbers.chunks_mut(2) {
+///         s.spawn(move || for n in chunk.iter_mut() { *n *= 10 });
+///     }
+/// });
+/// assert_eq!(numbers, [10, 20, 30, 40]);
+/// ```
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub fn scope<'env, F, R>(f: F) -> R
+    where F: FnOnce(&Scope<'env>) -> R
+{
+    let scope = Scope { threads: Mutex::new(Vec::new()) };
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(&scope)));
+    let child_panic = scope.join_all();
+    match (result, child_panic) {
+        (Err(payload), _) | (Ok(_), Some(payload)) => panic::resume_unwind(payload),
+        (Ok(result), None) => result,
+    }
+}
+
+/// A scope in which threads borrowing from outside of it can be spawned.
+///
+/// See the `scope` function.
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub struct Scope<'env> {
+    /// The threads spawned in the scope, in order.
+    threads: Mutex<Vec<Arc<ScopedThread + Send + Sync + 'env>>>,
+}
+
+impl<'env> Scope<'env> {
+    /// Spawns a new thread in the scope, returning a handle for it.
+    ///
+    /// The thread is joined at the end of the scope if it is not joined
+    /// through the handle before then.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create a thread; use `Builder::spawn_scoped`
+    /// to recover from such errors.
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn spawn<'scope, F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+        where F: FnOnce() -> T + Send + 'env, T: Send + 'env
+    {
+        Builder::new().spawn_scoped(self, f).unwrap()
+    }
+
+    /// Joins every thread which has not been joined yet, returning the
+    /// payload of the first one which panicked.
+    fn join_all(&self) -> Option<Box<Any + Send + 'static>> {
+        let threads = {
+            let mut threads = self.threads.lock().unwrap_or_else(|e| e.into_inner());
+            mem::replace(&mut *threads, Vec::new())
+        };
+        let mut first_panic = None;
+        for thread in threads {
+            if let Some(payload) = thread.join() {
+                if first_panic.is_none() {
+                    first_panic = Some(payload);
+                }
+            }
+        }
+        first_panic
+    }
+}
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+impl<'env> fmt::Debug for Scope<'env> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Scope { .. }")
+    }
+}
+
+/// A thread spawned in a scope, with the type of its result erased.
+trait ScopedThread {
+    /// Joins the thread unless that was already done through its handle,
+    /// returning its panic payload if it panicked.
+    fn join(&self) -> Option<Box<Any + Send + 'static>>;
+}
+
+impl<T> ScopedThread for Mutex<Option<JoinInner<T>>> {
+    fn join(&self) -> Option<Box<Any + Send + 'static>> {
+        let inner = self.lock().unwrap_or_else(|e| e.into_inner()).take();
+        inner.and_then(|mut inner| inner.join().err())
+    }
+}
+
+impl Builder {
+    /// Spawns a new thread in `scope`, and returns a join handle for it.
+    ///
+    /// This is like `Scope::spawn`, but uses the configuration of the
+    /// builder.
+    ///
+    /// # Errors
+    ///
+    /// Unlike `Scope::spawn`, this method yields an `io::Result` to capture
+    /// any failure to create the thread at the OS level.
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn spawn_scoped<'scope, 'env, F, T>(self, scope: &'scope Scope<'env>, f: F)
+                                            -> io::Result<ScopedJoinHandle<'scope, T>>
+        where F: FnOnce() -> T + Send + 'env, T: Send + 'env
+    {
+        // This is safe because the scope joins the thread before it ends,
+        // and so before anything borrowed for `'env` can go away.
+        let inner = unsafe { self.spawn_unchecked(f)? };
+        let thread = inner.thread.clone();
+        let inner = Arc::new(Mutex::new(Some(inner)));
+        scope.threads.lock().unwrap_or_else(|e| e.into_inner()).push(inner.clone());
+        Ok(ScopedJoinHandle {
+            inner: inner,
+            thread: thread,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// A permission to join on a thread spawned in a scope.
+///
+/// Dropping the handle does not detach the thread; it is still joined at
+/// the end of the scope.
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub struct ScopedJoinHandle<'scope, T> {
+    inner: Arc<Mutex<Option<JoinInner<T>>>>,
+    thread: Thread,
+    _marker: PhantomData<&'scope ()>,
+}
+
+impl<'scope, T> ScopedJoinHandle<'scope, T> {
+    /// Extracts a handle to the underlying thread.
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn thread(&self) -> &Thread {
+        &self.thread
+    }
+
+    /// Waits for the associated thread to finish.
+    ///
+    /// If the child thread panics, `Err` is returned with the parameter given
+    /// to `panic`, and the panic is not propagated by `scope`.
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn join(self) -> Result<T> {
+        // The scope only joins threads once the closure given to `scope`,
+        // and so every handle, is gone.
+        let mut inner = self.inner.lock().unwrap().take().unwrap();
+        inner.join()
+    }
+}
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+impl<'scope, T> fmt::Debug for ScopedJoinHandle<'scope, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScopedJoinHandle").field("thread", &self.thread).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::v1::*;
+
+    use panic;
+    use sync::atomic::{AtomicUsize, Ordering};
+    use thread;
+    use time::Duration;
+
+    #[test]
+    fn borrows_from_the_stack() {
+        let mut numbers = vec![0; 8];
+        let sum = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for (i, n) in numbers.iter_mut().enumerate() {
+                let sum = &sum;
+                s.spawn(move || {
+                    *n = i;
+                    sum.fetch_add(i, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(numbers, [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(sum.load(Ordering::SeqCst), 28);
+    }
+
+    #[test]
+    fn join_returns_result() {
+        let numbers = vec![1, 2, 3];
+        let (sum, len) = thread::scope(|s| {
+            let sum = s.spawn(|| numbers.iter().fold(0, |a, b| a + b));
+            let len = s.spawn(|| numbers.len());
+            (sum.join().unwrap(), len.join().unwrap())
+        });
+        assert_eq!(sum, 6);
+        assert_eq!(len, 3);
+    }
+
+    #[test]
+    fn threads_are_joined_at_end_of_scope() {
+        let done = AtomicUsize::new(0);
+        thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                done.store(1, Ordering::SeqCst);
+            });
+        });
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn child_panic_propagates() {
+        let result = panic::catch_unwind(|| {
+            thread::scope(|s| {
+                s.spawn(|| panic!("child"));
+                s.spawn(|| ());
+            })
+        });
+        let payload = result.unwrap_err();
+        assert_eq!(*payload.downcast_ref::<&'static str>().unwrap(), "child");
+    }
+
+    #[test]
+    fn joined_panic_does_not_propagate() {
+        let result = thread::scope(|s| s.spawn(|| panic!("child")).join().is_err());
+        assert!(result);
+    }
+
+    #[test]
+    fn scope_panic_waits_for_children() {
+        let done = AtomicUsize::new(0);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            thread::scope(|s| {
+                s.spawn(|| {
+                    thread::sleep(Duration::from_millis(50));
+                    done.store(1, Ordering::SeqCst);
+                });
+                panic!("scope");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+}
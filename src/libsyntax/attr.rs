@@ -425,6 +425,8 @@ pub struct RustcDeprecation {
 pub struct Deprecation {
     pub since: Option<InternedString>,
     pub note: Option<InternedString>,
+    /// The name of an item, next to the deprecated one, to use instead.
+    pub suggestion: Option<InternedString>,
 }
 
 impl StabilityLevel {
@@ -656,10 +658,12 @@ fn find_deprecation_generic<'a, I>(diagnostic: &Handler,
 
             let mut since = None;
             let mut note = None;
+            let mut suggestion = None;
             for meta in metas {
                 match &*meta.name() {
                     "since" => if !get(meta, &mut since) { continue 'outer },
                     "note" => if !get(meta, &mut note) { continue 'outer },
+                    "suggestion" => if !get(meta, &mut suggestion) { continue 'outer },
                     _ => {
                         diagnostic.span_err(meta.span, &format!("unknown meta item '{}'",
                                                                 meta.name()));
@@ -668,9 +672,9 @@ fn find_deprecation_generic<'a, I>(diagnostic: &Handler,
                 }
             }
 
-            Some(Deprecation {since: since, note: note})
+            Some(Deprecation {since: since, note: note, suggestion: suggestion})
         } else {
-            Some(Deprecation{since: None, note: None})
+            Some(Deprecation{since: None, note: None, suggestion: None})
         }
     }
 
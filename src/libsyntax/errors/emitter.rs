@@ -706,6 +706,7 @@ mod test {
         let suggest = CodeSuggestion {
             msp: msp,
             substitutes: vec![substitute],
+            applicable: false,
         };
         assert_eq!(suggest.splice_lines(&cm), expected);
     }
@@ -726,7 +727,8 @@ mod test {
         let suggest = CodeSuggestion {
             msp: msp,
             substitutes: vec!["ZZZZZZ".to_owned(),
-                              "XYZ".to_owned()]
+                              "XYZ".to_owned()],
+            applicable: false,
         };
 
         assert_eq!(suggest.splice_lines(&cm), expected);
@@ -0,0 +1,303 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Turning code suggestions into edits of the source files.
+//!
+//! Suggestions are collected by the `Handler` while diagnostics are emitted
+//! (see `Handler::collect_suggestions`). Once compilation is over they are
+//! converted into byte-range edits of the files they apply to. Only the
+//! suggestions marked as applicable (see
+//! `DiagnosticBuilder::span_applicable_suggestion`) are used. Suggestions
+//! pointing into macro expansions or into files we don't have the source
+//! of can't be applied mechanically and are skipped, as is any suggestion
+//! which overlaps one that was accepted before it.
+
+use codemap::{CodeMap, FileMap, NO_EXPANSION, Pos};
+use errors::CodeSuggestion;
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+/// Lines of unchanged context around each hunk of a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Replacement of the bytes `lo..hi` of a file with `text`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Edit {
+    pub lo: usize,
+    pub hi: usize,
+    pub text: String,
+}
+
+impl Edit {
+    fn overlaps(&self, other: &Edit) -> bool {
+        // Two insertions at the same point conflict as well, since their
+        // order would be arbitrary.
+        self.lo < other.hi && other.lo < self.hi ||
+        self.lo == other.lo && (self.lo == self.hi || other.lo == other.hi)
+    }
+}
+
+/// The edits to make to a single file.
+pub struct FileFix {
+    pub file_name: String,
+    /// The source the edits refer to.
+    pub src: Rc<String>,
+    /// Sorted and non-overlapping.
+    pub edits: Vec<Edit>,
+}
+
+impl FileFix {
+    /// The source of the file with all edits applied.
+    pub fn apply(&self) -> String {
+        apply_edits(&self.src, &self.edits)
+    }
+
+    /// A unified diff between the original and the edited source.
+    pub fn diff(&self) -> String {
+        unified_diff(&self.file_name, &self.src, &self.edits)
+    }
+}
+
+/// The result of resolving a set of suggestions.
+pub struct Fixes {
+    /// Keyed by file name.
+    pub files: BTreeMap<String, FileFix>,
+    /// Number of suggestions which could not be applied.
+    pub skipped: usize,
+}
+
+/// Converts `suggestions` into edits. Suggestions are considered in order,
+/// and an edit overlapping one accepted earlier causes its whole suggestion
+/// to be skipped. Suggesting the very same edit twice is not a conflict.
+pub fn resolve_suggestions(cm: &CodeMap, suggestions: &[CodeSuggestion]) -> Fixes {
+    let mut fixes = Fixes { files: BTreeMap::new(), skipped: 0 };
+
+    'suggestions: for suggestion in suggestions {
+        let (fm, edits) = match suggestion_edits(cm, suggestion) {
+            Some(edits) => edits,
+            None => {
+                fixes.skipped += 1;
+                continue
+            }
+        };
+
+        let file = fixes.files.entry(fm.name.clone()).or_insert_with(|| {
+            FileFix {
+                file_name: fm.name.clone(),
+                src: fm.src.clone().unwrap(),
+                edits: Vec::new(),
+            }
+        });
+        let mut new_edits = Vec::new();
+        for edit in edits {
+            if file.edits.contains(&edit) {
+                continue
+            }
+            if file.edits.iter().chain(&new_edits).any(|e| e.overlaps(&edit)) {
+                fixes.skipped += 1;
+                continue 'suggestions
+            }
+            new_edits.push(edit);
+        }
+        file.edits.extend(new_edits);
+        file.edits.sort_by_key(|e| (e.lo, e.hi));
+    }
+
+    fixes.files = fixes.files.into_iter().filter(|&(_, ref f)| !f.edits.is_empty()).collect();
+    fixes
+}
+
+/// The edits making up a single suggestion, or `None` if it isn't marked as
+/// applicable or can't be applied mechanically.
+fn suggestion_edits(cm: &CodeMap,
+                    suggestion: &CodeSuggestion)
+                    -> Option<(Rc<FileMap>, Vec<Edit>)> {
+    let spans = suggestion.msp.primary_spans();
+    if !suggestion.applicable || spans.is_empty() ||
+       spans.len() != suggestion.substitutes.len() {
+        return None;
+    }
+
+    let mut file = None;
+    let mut edits = Vec::new();
+    for (sp, substitute) in spans.iter().zip(&suggestion.substitutes) {
+        if sp.expn_id != NO_EXPANSION || sp.lo > sp.hi {
+            return None;
+        }
+        let lo = cm.lookup_byte_offset(sp.lo);
+        let hi = cm.lookup_byte_offset(sp.hi);
+        if !lo.fm.is_real_file() || lo.fm.is_imported() || lo.fm.name != hi.fm.name {
+            return None;
+        }
+        match file {
+            Some(ref fm) if fm.start_pos != lo.fm.start_pos => return None,
+            _ => {}
+        }
+        edits.push(Edit {
+            lo: lo.pos.to_usize(),
+            hi: hi.pos.to_usize(),
+            text: substitute.clone(),
+        });
+        file = Some(lo.fm);
+    }
+    file.map(|fm| (fm, edits))
+}
+
+/// Applies `edits`, which must be sorted and non-overlapping, to `src`.
+pub fn apply_edits(src: &str, edits: &[Edit]) -> String {
+    let mut result = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        result.push_str(&src[pos..edit.lo]);
+        result.push_str(&edit.text);
+        pos = edit.hi;
+    }
+    result.push_str(&src[pos..]);
+    result
+}
+
+/// Builds a unified diff of the changes `edits` make to `src`.
+///
+/// Rather than diffing the whole file, every edit is turned into a hunk
+/// covering the lines it touches; hunks close to each other are merged.
+pub fn unified_diff(file_name: &str, src: &str, edits: &[Edit]) -> String {
+    let line_starts = line_starts(src);
+    let line_of = |pos: usize| match line_starts.binary_search(&pos) {
+        Ok(line) => line,
+        Err(line) => line - 1,
+    };
+
+    // Group the edits into hunks, as `(first line, last line, edits)`.
+    let mut hunks: Vec<(usize, usize, Vec<&Edit>)> = Vec::new();
+    for edit in edits {
+        let first = line_of(edit.lo);
+        let last = if edit.hi > edit.lo { line_of(edit.hi - 1) } else { first };
+        if let Some(hunk) = hunks.last_mut() {
+            if first <= hunk.1 + 2 * DIFF_CONTEXT + 1 {
+                hunk.1 = cmp::max(hunk.1, last);
+                hunk.2.push(edit);
+                continue
+            }
+        }
+        hunks.push((first, last, vec![edit]));
+    }
+
+    let num_lines = line_starts.len();
+    let line_end = |line: usize| {
+        if line + 1 < num_lines { line_starts[line + 1] } else { src.len() }
+    };
+
+    let mut out = format!("--- {}\n+++ {}\n", file_name, file_name);
+    // Difference between line numbers in the old and new file so far.
+    let mut offset = 0isize;
+    for (first, last, hunk_edits) in hunks {
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let end = cmp::min(num_lines - 1, last + DIFF_CONTEXT);
+        let (lo, hi) = (line_starts[start], line_end(end));
+
+        let old = &src[lo..hi];
+        let shifted = hunk_edits.iter().map(|e| {
+            Edit { lo: e.lo - lo, hi: e.hi - lo, text: e.text.clone() }
+        }).collect::<Vec<_>>();
+        let new = apply_edits(old, &shifted);
+
+        let old_lines = old.lines().collect::<Vec<_>>();
+        let new_lines = new.lines().collect::<Vec<_>>();
+        out.push_str(&format!("@@ -{},{} +{},{} @@\n",
+                              start + 1,
+                              old_lines.len(),
+                              start as isize + offset + 1,
+                              new_lines.len()));
+        for line in diff_lines(&old_lines, &new_lines) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        offset += new_lines.len() as isize - old_lines.len() as isize;
+    }
+    out
+}
+
+fn line_starts(src: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(src.char_indices().filter(|&(i, c)| c == '\n' && i + 1 < src.len())
+                                    .map(|(i, _)| i + 1));
+    starts
+}
+
+/// A line-based diff of two (short) sequences of lines, computed from their
+/// longest common subsequence.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<String> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            lines.push(format!(" {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("-{}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+{}", new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_edits, unified_diff, Edit};
+
+    fn edit(lo: usize, hi: usize, text: &str) -> Edit {
+        Edit { lo: lo, hi: hi, text: text.to_string() }
+    }
+
+    #[test]
+    fn apply() {
+        let src = "let x = y as Trait;";
+        let edits = [edit(4, 5, "mut x"), edit(13, 18, "&Trait")];
+        assert_eq!(apply_edits(src, &edits), "let mut x = y as &Trait;");
+        assert_eq!(apply_edits(src, &[]), src);
+    }
+
+    #[test]
+    fn overlaps() {
+        assert!(edit(0, 4, "").overlaps(&edit(2, 6, "")));
+        assert!(edit(2, 2, "a").overlaps(&edit(2, 2, "b")));
+        assert!(!edit(0, 2, "").overlaps(&edit(2, 4, "")));
+        assert!(!edit(0, 4, "").overlaps(&edit(4, 4, "x")));
+    }
+
+    #[test]
+    fn diff() {
+        let src = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\no\np\n";
+        // Replace `b` and `o`, far enough apart to get separate hunks.
+        let edits = [edit(2, 3, "B\nB2"), edit(28, 29, "O")];
+        let expected = "--- lib.rs\n+++ lib.rs\n\
+                        @@ -1,5 +1,6 @@\n a\n-b\n+B\n+B2\n c\n d\n e\n\
+                        @@ -12,5 +13,5 @@\n l\n m\n n\n-o\n+O\n p\n";
+        assert_eq!(unified_diff("lib.rs", src, &edits), expected);
+    }
+}
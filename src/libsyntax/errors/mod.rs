@@ -18,13 +18,17 @@ use diagnostics;
 use errors::emitter::{Emitter, EmitterWriter};
 
 use std::cell::{RefCell, Cell};
+use std::collections::HashSet;
 use std::{error, fmt};
 use std::rc::Rc;
 use std::thread::panicking;
 use term;
 
 pub mod emitter;
+pub mod fix;
 pub mod json;
+pub mod sarif;
+pub mod short;
 pub mod snippet;
 
 #[derive(Clone)]
@@ -45,6 +49,9 @@ pub enum RenderSpan {
 pub struct CodeSuggestion {
     msp: MultiSpan,
     substitutes: Vec<String>,
+    /// Whether the suggestion is known to be what the user meant, and to
+    /// result in valid code, so that it can be applied without review.
+    applicable: bool,
 }
 
 impl RenderSpan {
@@ -184,6 +191,8 @@ pub struct DiagnosticBuilder<'a> {
     level: Level,
     message: String,
     code: Option<String>,
+    /// The name of the lint this diagnostic was reported for, if any.
+    lint: Option<String>,
     span: MultiSpan,
     children: Vec<SubDiagnostic>,
 }
@@ -204,9 +213,33 @@ impl<'a> DiagnosticBuilder<'a> {
             return;
         }
 
+        let is_error = self.level == Level::Error || self.level == Level::Fatal;
+        if !self.handler.is_new_diagnostic(&self.span,
+                                           &self.message,
+                                           self.code.as_ref().map(|c| &c[..]),
+                                           self.level) {
+            // The error was counted when this builder was created.
+            if is_error {
+                self.handler.err_count.set(self.handler.err_count.get() - 1);
+            }
+            self.cancel();
+            return;
+        }
+
         self.handler.emit.borrow_mut().emit_struct(&self);
+        if let Some(ref mut suggestions) = *self.handler.suggestions.borrow_mut() {
+            suggestions.extend(self.children.iter().filter_map(|child| {
+                match child.render_span {
+                    Some(Suggestion(ref suggestion)) => Some(suggestion.clone()),
+                    _ => None,
+                }
+            }));
+        }
         self.cancel();
         self.handler.panic_if_treat_err_as_bug();
+        if is_error {
+            self.handler.check_error_limit();
+        }
 
         // if self.is_fatal() {
         //     panic!(FatalError);
@@ -298,6 +331,21 @@ impl<'a> DiagnosticBuilder<'a> {
         self.sub(Level::Help, msg, MultiSpan::new(), Some(Suggestion(CodeSuggestion {
             msp: sp.into(),
             substitutes: vec![suggestion],
+            applicable: false,
+        })));
+        self
+    }
+    /// Like `span_suggestion`, for suggestions which are known to be right,
+    /// and which `-Z apply-suggestions` applies.
+    pub fn span_applicable_suggestion<S: Into<MultiSpan>>(&mut self,
+                                                          sp: S,
+                                                          msg: &str,
+                                                          suggestion: String)
+                                                          -> &mut DiagnosticBuilder<'a> {
+        self.sub(Level::Help, msg, MultiSpan::new(), Some(Suggestion(CodeSuggestion {
+            msp: sp.into(),
+            substitutes: vec![suggestion],
+            applicable: true,
         })));
         self
     }
@@ -312,6 +360,11 @@ impl<'a> DiagnosticBuilder<'a> {
         self
     }
 
+    pub fn lint(&mut self, name: &str) -> &mut Self {
+        self.lint = Some(name.to_owned());
+        self
+    }
+
     pub fn message(&self) -> &str {
         &self.message
     }
@@ -330,6 +383,7 @@ impl<'a> DiagnosticBuilder<'a> {
             level: level,
             message: message.to_owned(),
             code: None,
+            lint: None,
             span: MultiSpan::new(),
             children: vec![],
         }
@@ -382,6 +436,17 @@ pub struct Handler {
     treat_err_as_bug: bool,
     continue_after_error: Cell<bool>,
     delayed_span_bug: RefCell<Option<(MultiSpan, String)>>,
+    /// The code suggestions of all emitted diagnostics, if they are being
+    /// collected.
+    suggestions: RefCell<Option<Vec<CodeSuggestion>>>,
+    /// The level, code, primary spans and message of every diagnostic
+    /// emitted so far; identical diagnostics are only emitted once.
+    emitted_diagnostics: RefCell<HashSet<(Level, Option<String>, Vec<Span>, String)>>,
+    suppressed_duplicates: Cell<usize>,
+    /// Whether identical diagnostics are suppressed.
+    deduplicate: Cell<bool>,
+    /// Compilation is aborted once this many errors have been emitted.
+    error_limit: Cell<Option<usize>>,
 }
 
 impl Handler {
@@ -405,6 +470,34 @@ impl Handler {
             treat_err_as_bug: treat_err_as_bug,
             continue_after_error: Cell::new(true),
             delayed_span_bug: RefCell::new(None),
+            suggestions: RefCell::new(None),
+            emitted_diagnostics: RefCell::new(HashSet::new()),
+            suppressed_duplicates: Cell::new(0),
+            deduplicate: Cell::new(true),
+            error_limit: Cell::new(None),
+        }
+    }
+
+    pub fn set_error_limit(&self, error_limit: Option<usize>) {
+        self.error_limit.set(error_limit);
+    }
+
+    pub fn set_deduplicate(&self, deduplicate: bool) {
+        self.deduplicate.set(deduplicate);
+    }
+
+    /// Starts keeping the code suggestions of all diagnostics emitted from
+    /// now on, so that they can be applied with `errors::fix`.
+    pub fn collect_suggestions(&self) {
+        *self.suggestions.borrow_mut() = Some(Vec::new());
+    }
+
+    /// Returns the suggestions collected so far, in the order in which their
+    /// diagnostics were emitted.
+    pub fn take_suggestions(&self) -> Vec<CodeSuggestion> {
+        match *self.suggestions.borrow_mut() {
+            Some(ref mut suggestions) => suggestions.drain(..).collect(),
+            None => Vec::new(),
         }
     }
 
@@ -504,6 +597,48 @@ impl Handler {
         err.cancel();
     }
 
+    /// Records that a diagnostic is about to be emitted. Returns false, and
+    /// counts it as suppressed, if an identical one was emitted before.
+    fn is_new_diagnostic(&self,
+                         msp: &MultiSpan,
+                         msg: &str,
+                         code: Option<&str>,
+                         lvl: Level) -> bool {
+        if !self.deduplicate.get() {
+            return true;
+        }
+        let key = (lvl,
+                   code.map(|c| c.to_owned()),
+                   msp.primary_spans().to_owned(),
+                   msg.to_owned());
+        if self.emitted_diagnostics.borrow_mut().insert(key) {
+            true
+        } else {
+            self.suppressed_duplicates.set(self.suppressed_duplicates.get() + 1);
+            false
+        }
+    }
+
+    /// Aborts compilation if the `-Z error-limit` has been reached.
+    fn check_error_limit(&self) {
+        match self.error_limit.get() {
+            Some(limit) if self.err_count.get() >= limit => {
+                self.note_suppressed_duplicates();
+                panic!(self.fatal(&format!("aborting after {} errors (the error limit)",
+                                           self.err_count.get())));
+            }
+            _ => {}
+        }
+    }
+
+    fn note_suppressed_duplicates(&self) {
+        match self.suppressed_duplicates.get() {
+            0 => {}
+            1 => self.note_without_error("1 duplicate diagnostic was suppressed"),
+            n => self.note_without_error(&format!("{} duplicate diagnostics were suppressed", n)),
+        }
+    }
+
     fn panic_if_treat_err_as_bug(&self) {
         if self.treat_err_as_bug {
             panic!("encountered error with `-Z treat_err_as_bug");
@@ -512,27 +647,33 @@ impl Handler {
 
     pub fn span_fatal<S: Into<MultiSpan>>(&self, sp: S, msg: &str)
                                           -> FatalError {
-        self.emit(&sp.into(), msg, Fatal);
-        self.bump_err_count();
-        self.panic_if_treat_err_as_bug();
+        if self.emit_diagnostic(&sp.into(), msg, None, Fatal) {
+            self.bump_err_count();
+            self.panic_if_treat_err_as_bug();
+        }
         return FatalError;
     }
     pub fn span_fatal_with_code<S: Into<MultiSpan>>(&self, sp: S, msg: &str, code: &str)
                                                     -> FatalError {
-        self.emit_with_code(&sp.into(), msg, code, Fatal);
-        self.bump_err_count();
-        self.panic_if_treat_err_as_bug();
+        if self.emit_diagnostic(&sp.into(), msg, Some(code), Fatal) {
+            self.bump_err_count();
+            self.panic_if_treat_err_as_bug();
+        }
         return FatalError;
     }
     pub fn span_err<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
-        self.emit(&sp.into(), msg, Error);
-        self.bump_err_count();
-        self.panic_if_treat_err_as_bug();
+        if self.emit_diagnostic(&sp.into(), msg, None, Error) {
+            self.bump_err_count();
+            self.panic_if_treat_err_as_bug();
+            self.check_error_limit();
+        }
     }
     pub fn span_err_with_code<S: Into<MultiSpan>>(&self, sp: S, msg: &str, code: &str) {
-        self.emit_with_code(&sp.into(), msg, code, Error);
-        self.bump_err_count();
-        self.panic_if_treat_err_as_bug();
+        if self.emit_diagnostic(&sp.into(), msg, Some(code), Error) {
+            self.bump_err_count();
+            self.panic_if_treat_err_as_bug();
+            self.check_error_limit();
+        }
     }
     pub fn span_warn<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
         self.emit(&sp.into(), msg, Warning);
@@ -619,29 +760,39 @@ impl Handler {
             }
         }
 
+        self.note_suppressed_duplicates();
         panic!(self.fatal(&s));
     }
     pub fn emit(&self,
                 msp: &MultiSpan,
                 msg: &str,
                 lvl: Level) {
-        if lvl == Warning && !self.can_emit_warnings { return }
-        self.emit.borrow_mut().emit(&msp, msg, None, lvl);
-        if !self.continue_after_error.get() { self.abort_if_errors(); }
+        self.emit_diagnostic(msp, msg, None, lvl);
     }
     pub fn emit_with_code(&self,
                           msp: &MultiSpan,
                           msg: &str,
                           code: &str,
                           lvl: Level) {
-        if lvl == Warning && !self.can_emit_warnings { return }
-        self.emit.borrow_mut().emit(&msp, msg, Some(code), lvl);
+        self.emit_diagnostic(msp, msg, Some(code), lvl);
+    }
+    /// Returns whether the diagnostic was emitted, i.e. it is neither a
+    /// disabled warning nor a duplicate.
+    fn emit_diagnostic(&self,
+                       msp: &MultiSpan,
+                       msg: &str,
+                       code: Option<&str>,
+                       lvl: Level) -> bool {
+        if lvl == Warning && !self.can_emit_warnings { return false }
+        if !self.is_new_diagnostic(msp, msg, code, lvl) { return false }
+        self.emit.borrow_mut().emit(&msp, msg, code, lvl);
         if !self.continue_after_error.get() { self.abort_if_errors(); }
+        true
     }
 }
 
 
-#[derive(Copy, PartialEq, Clone, Debug)]
+#[derive(Copy, PartialEq, Eq, Hash, Clone, Debug)]
 pub enum Level {
     Bug,
     Fatal,
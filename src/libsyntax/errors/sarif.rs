@@ -0,0 +1,326 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An emitter producing a SARIF log (`--error-format sarif`).
+//!
+//! SARIF (Static Analysis Results Interchange Format, version 2.1.0) is a
+//! JSON format understood by code review and static analysis tooling.
+//! Unlike the other emitters this one doesn't print anything while the
+//! compiler runs: diagnostics are collected as SARIF results and a single
+//! log, containing all of them, is written when the emitter is dropped at
+//! the end of the compilation.
+//!
+//! Every error code and lint which occurs becomes a rule of the log; the
+//! long explanations from the diagnostics `Registry` are used as the full
+//! description of error codes. Suggestions are turned into SARIF fixes.
+
+use codemap::{CodeMap, MultiSpan, Span};
+use diagnostics::registry::Registry;
+use errors::{Level, DiagnosticBuilder, RenderSpan};
+use errors::emitter::Emitter;
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use rustc_serialize::json::Json;
+
+const SARIF_SCHEMA: &'static str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &'static str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    cm: Rc<CodeMap>,
+    /// Keyed by rule id, i.e. error code or lint name.
+    rules: BTreeMap<String, Json>,
+    results: Vec<Json>,
+}
+
+impl SarifEmitter {
+    pub fn basic() -> SarifEmitter {
+        SarifEmitter::stderr(None, Rc::new(CodeMap::new()))
+    }
+
+    pub fn stderr(registry: Option<Registry>, code_map: Rc<CodeMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), registry, code_map)
+    }
+
+    pub fn new(dst: Box<Write + Send>,
+               registry: Option<Registry>,
+               code_map: Rc<CodeMap>) -> SarifEmitter {
+        SarifEmitter {
+            dst: dst,
+            registry: registry,
+            cm: code_map,
+            rules: BTreeMap::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// The complete log for all diagnostics emitted so far.
+    pub fn log(&self) -> Json {
+        let driver = object(vec![
+            ("name", Json::String("rustc".to_owned())),
+            ("informationUri", Json::String("https://www.rust-lang.org/".to_owned())),
+            ("rules", Json::Array(self.rules.values().cloned().collect())),
+        ]);
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            ("results", Json::Array(self.results.clone())),
+        ]);
+        object(vec![
+            ("$schema", Json::String(SARIF_SCHEMA.to_owned())),
+            ("version", Json::String(SARIF_VERSION.to_owned())),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+
+    fn add_rule(&mut self, id: &str, is_lint: bool) {
+        if self.rules.contains_key(id) {
+            return;
+        }
+        let mut rule = vec![("id", Json::String(id.to_owned()))];
+        let explanation = self.registry.as_ref().and_then(|r| r.find_description(id));
+        if let Some(explanation) = explanation {
+            rule.push(("fullDescription", message(explanation.trim())));
+            rule.push(("helpUri", Json::String(format!(
+                "https://doc.rust-lang.org/error-index.html#{}", id))));
+        }
+        if is_lint {
+            rule.push(("properties", object(vec![
+                ("tags", Json::Array(vec![Json::String("lint".to_owned())])),
+            ])));
+        }
+        self.rules.insert(id.to_owned(), object(rule));
+    }
+
+    fn add_result(&mut self,
+                  msp: &MultiSpan,
+                  text: String,
+                  code: Option<&str>,
+                  lint: Option<&str>,
+                  level: Level,
+                  related_locations: Vec<Json>,
+                  fixes: Vec<Json>) {
+        let mut result = vec![
+            ("level", Json::String(sarif_level(level).to_owned())),
+            ("message", message(&text)),
+            ("locations", Json::Array(msp.primary_spans().iter().map(|&sp| {
+                self.location(sp, None)
+            }).collect())),
+        ];
+        if let Some(rule_id) = code.or(lint) {
+            result.push(("ruleId", Json::String(rule_id.to_owned())));
+            self.add_rule(rule_id, code.is_none());
+        }
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Json::Array(related_locations)));
+        }
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+        self.results.push(object(result));
+    }
+
+    fn region(&self, sp: Span) -> Json {
+        let start = self.cm.lookup_char_pos(sp.lo);
+        let end = self.cm.lookup_char_pos(sp.hi);
+        object(vec![
+            ("startLine", Json::U64(start.line as u64)),
+            ("startColumn", Json::U64(start.col.0 as u64 + 1)),
+            ("endLine", Json::U64(end.line as u64)),
+            ("endColumn", Json::U64(end.col.0 as u64 + 1)),
+        ])
+    }
+
+    fn artifact_location(&self, sp: Span) -> Json {
+        let file = self.cm.lookup_char_pos(sp.lo).file;
+        object(vec![("uri", Json::String(file.name.clone()))])
+    }
+
+    fn location(&self, sp: Span, text: Option<&str>) -> Json {
+        let mut location = vec![
+            ("physicalLocation", object(vec![
+                ("artifactLocation", self.artifact_location(sp)),
+                ("region", self.region(sp)),
+            ])),
+        ];
+        if let Some(text) = text {
+            location.push(("message", message(text)));
+        }
+        object(location)
+    }
+
+    fn fix(&self, description: &str, spans: &[Span], substitutes: &[String]) -> Json {
+        // SARIF groups the replacements of a fix by file.
+        let mut changes: Vec<(Span, Vec<Json>)> = Vec::new();
+        for (&sp, substitute) in spans.iter().zip(substitutes) {
+            let replacement = object(vec![
+                ("deletedRegion", self.region(sp)),
+                ("insertedContent", object(vec![("text", Json::String(substitute.clone()))])),
+            ]);
+            let file = self.cm.lookup_char_pos(sp.lo).file;
+            match changes.iter().position(|&(other, _)| {
+                self.cm.lookup_char_pos(other.lo).file.name == file.name
+            }) {
+                Some(i) => changes[i].1.push(replacement),
+                None => changes.push((sp, vec![replacement])),
+            }
+        }
+        object(vec![
+            ("description", message(description)),
+            ("artifactChanges", Json::Array(changes.into_iter().map(|(sp, replacements)| {
+                object(vec![
+                    ("artifactLocation", self.artifact_location(sp)),
+                    ("replacements", Json::Array(replacements)),
+                ])
+            }).collect())),
+        ])
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, span: &MultiSpan, msg: &str, code: Option<&str>, level: Level) {
+        self.add_result(span, msg.to_owned(), code, None, level, vec![], vec![]);
+    }
+
+    fn emit_struct(&mut self, db: &DiagnosticBuilder) {
+        // Children with a location of their own become related locations,
+        // all others are appended to the message.
+        let mut text = db.message.clone();
+        let mut related_locations = vec![];
+        let mut fixes = vec![];
+        for child in &db.children {
+            let child_text = format!("{}: {}", child.level, child.message);
+            match child.render_span {
+                Some(RenderSpan::Suggestion(ref suggestion)) => {
+                    fixes.push(self.fix(&child.message,
+                                        suggestion.msp.primary_spans(),
+                                        &suggestion.substitutes));
+                    text.push_str("\n");
+                    text.push_str(&child_text);
+                }
+                Some(RenderSpan::FullSpan(ref msp)) if msp.primary_span().is_some() => {
+                    related_locations.extend(msp.primary_spans().iter().map(|&sp| {
+                        self.location(sp, Some(&child_text))
+                    }));
+                }
+                None if child.span.primary_span().is_some() => {
+                    related_locations.extend(child.span.primary_spans().iter().map(|&sp| {
+                        self.location(sp, Some(&child_text))
+                    }));
+                }
+                _ => {
+                    text.push_str("\n");
+                    text.push_str(&child_text);
+                }
+            }
+        }
+        self.add_result(&db.span,
+                        text,
+                        db.code.as_ref().map(|c| &c[..]),
+                        db.lint.as_ref().map(|l| &l[..]),
+                        db.level,
+                        related_locations,
+                        fixes);
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        // The emitter may be dropped while unwinding from a fatal error, when
+        // panicking again would abort the process.
+        let log = self.log();
+        if let Err(e) = writeln!(&mut self.dst, "{}", log) {
+            let _ = writeln!(&mut io::stderr(), "failed to print diagnostics: {}", e);
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::Cancelled => "note",
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+fn message(text: &str) -> Json {
+    object(vec![("text", Json::String(text.to_owned()))])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SarifEmitter;
+
+    use codemap::{BytePos, CodeMap, MultiSpan, Span, NO_EXPANSION};
+    use diagnostics::registry::Registry;
+    use errors::{DiagnosticBuilder, Handler, Level};
+    use errors::emitter::Emitter;
+
+    use std::io;
+    use std::rc::Rc;
+
+    /// Emitter for the handler creating the diagnostics, which are then
+    /// passed to the `SarifEmitter` by hand.
+    struct NullEmitter;
+    impl Emitter for NullEmitter {
+        fn emit(&mut self, _: &MultiSpan, _: &str, _: Option<&str>, _: Level) {}
+        fn emit_struct(&mut self, _: &DiagnosticBuilder) {}
+    }
+
+    #[test]
+    fn results_and_rules() {
+        let cm = Rc::new(CodeMap::new());
+        cm.new_filemap_and_lines("foo.rs", "fn main() {\n    let x: u32 = \"a\";\n}\n");
+        let sp = Span { lo: BytePos(29), hi: BytePos(32), expn_id: NO_EXPANSION };
+        let registry = Registry::new(&[("E0308", "Mismatched types.\n")]);
+
+        let mut emitter = SarifEmitter::new(Box::new(io::sink()), Some(registry), cm.clone());
+        let handler = Handler::with_emitter(true, false, Box::new(NullEmitter));
+        let mut db = handler.struct_span_err_with_code(sp, "mismatched types", "E0308");
+        db.note("expected type `u32`");
+        db.span_suggestion(sp, "try a number", "1".to_owned());
+        emitter.emit_struct(&db);
+        db.cancel();
+
+        let mut db = handler.struct_span_warn(sp, "unused variable");
+        db.lint("unused_variables");
+        emitter.emit_struct(&db);
+        db.cancel();
+
+        let log = emitter.log();
+        let run = &log.find("runs").unwrap().as_array().unwrap()[0];
+        let rules = run.find_path(&["tool", "driver", "rules"]).unwrap().as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].find("id").unwrap().as_string(), Some("E0308"));
+        assert_eq!(rules[0].find_path(&["fullDescription", "text"]).unwrap().as_string(),
+                   Some("Mismatched types."));
+        assert_eq!(rules[1].find("id").unwrap().as_string(), Some("unused_variables"));
+
+        let results = run.find("results").unwrap().as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].find("level").unwrap().as_string(), Some("error"));
+        assert_eq!(results[0].find_path(&["message", "text"]).unwrap().as_string(),
+                   Some("mismatched types\nnote: expected type `u32`\nhelp: try a number"));
+        let region = results[0].find("locations").unwrap().as_array().unwrap()[0]
+                               .find_path(&["physicalLocation", "region"]).unwrap();
+        assert_eq!(region.find("startLine").unwrap().as_u64(), Some(2));
+        assert_eq!(region.find("startColumn").unwrap().as_u64(), Some(18));
+        assert_eq!(region.find("endColumn").unwrap().as_u64(), Some(21));
+        assert_eq!(results[0].find("fixes").unwrap().as_array().unwrap().len(), 1);
+        assert_eq!(results[1].find("ruleId").unwrap().as_string(), Some("unused_variables"));
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An emitter printing every diagnostic on a single line
+//! (`--error-format short`).
+//!
+//! Each line has the form `file:line:col: level[code]: message`, followed by
+//! the notes and help messages attached to the diagnostic, separated by
+//! `; `. This is the format understood by most editors and is convenient
+//! for grepping through build logs.
+
+use codemap::{CodeMap, MultiSpan, Span};
+use errors::{Level, DiagnosticBuilder, RenderSpan};
+use errors::emitter::Emitter;
+
+use std::io::{self, Write};
+use std::rc::Rc;
+
+pub struct ShortEmitter {
+    dst: Box<Write + Send>,
+    cm: Rc<CodeMap>,
+}
+
+impl ShortEmitter {
+    pub fn stderr(code_map: Rc<CodeMap>) -> ShortEmitter {
+        ShortEmitter::new(Box::new(io::stderr()), code_map)
+    }
+
+    pub fn new(dst: Box<Write + Send>, code_map: Rc<CodeMap>) -> ShortEmitter {
+        ShortEmitter {
+            dst: dst,
+            cm: code_map,
+        }
+    }
+
+    fn location(&self, sp: Span) -> String {
+        let loc = self.cm.lookup_char_pos(sp.lo);
+        format!("{}:{}:{}", loc.file.name, loc.line, loc.col.0 + 1)
+    }
+
+    fn header(&self, msp: &MultiSpan, msg: &str, code: Option<&str>, level: Level) -> String {
+        let mut line = String::new();
+        if let Some(sp) = msp.primary_span() {
+            line.push_str(&self.location(sp));
+            line.push_str(": ");
+        }
+        line.push_str(&level.to_string());
+        if let Some(code) = code {
+            line.push_str(&format!("[{}]", code));
+        }
+        line.push_str(": ");
+        line.push_str(&single_line(msg));
+        line
+    }
+
+    fn print(&mut self, line: &str) {
+        if let Err(e) = writeln!(&mut self.dst, "{}", line) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+impl Emitter for ShortEmitter {
+    fn emit(&mut self, span: &MultiSpan, msg: &str, code: Option<&str>, level: Level) {
+        let line = self.header(span, msg, code, level);
+        self.print(&line);
+    }
+
+    fn emit_struct(&mut self, db: &DiagnosticBuilder) {
+        let mut line = self.header(&db.span,
+                                   &db.message,
+                                   db.code.as_ref().map(|c| &c[..]),
+                                   db.level);
+        let primary = db.span.primary_span();
+
+        for child in &db.children {
+            line.push_str("; ");
+            line.push_str(&child.level.to_string());
+            line.push_str(": ");
+            line.push_str(&single_line(&child.message));
+
+            let span = match child.render_span {
+                Some(RenderSpan::Suggestion(ref suggestion)) => {
+                    let substitutes = suggestion.substitutes.iter().map(|s| {
+                        format!("`{}`", single_line(s))
+                    }).collect::<Vec<_>>();
+                    line.push_str(" ");
+                    line.push_str(&substitutes.join(", "));
+                    suggestion.msp.primary_span()
+                }
+                Some(RenderSpan::FullSpan(ref msp)) => msp.primary_span(),
+                None => child.span.primary_span(),
+            };
+            // Only mention the location of a child if it isn't the one of
+            // the diagnostic itself.
+            if let Some(sp) = span {
+                if primary.map_or(true, |p| p.lo != sp.lo) {
+                    line.push_str(&format!(" (at {})", self.location(sp)));
+                }
+            }
+        }
+
+        self.print(&line);
+    }
+}
+
+/// Joins the lines of a multi-line message.
+fn single_line(msg: &str) -> String {
+    msg.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{single_line, ShortEmitter};
+
+    use codemap::{BytePos, CodeMap, MultiSpan, Span, NO_EXPANSION};
+    use errors::{Handler, Level};
+
+    use std::io::{self, Write};
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl Write for Sink {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), data)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn single_lines() {
+        assert_eq!(single_line("expected `u32`,\n    found `&str`"),
+                   "expected `u32`, found `&str`");
+        assert_eq!(single_line("one line"), "one line");
+    }
+
+    #[test]
+    fn emit_struct() {
+        let cm = Rc::new(CodeMap::new());
+        cm.new_filemap_and_lines("foo.rs", "fn main() {\n    let x: u32 = \"a\";\n}\n");
+        let sp = Span { lo: BytePos(29), hi: BytePos(32), expn_id: NO_EXPANSION };
+
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let emitter = ShortEmitter::new(Box::new(Sink(data.clone())), cm.clone());
+        let handler = Handler::with_emitter(true, false, Box::new(emitter));
+        handler.struct_span_err_with_code(sp, "mismatched types", "E0308")
+               .note("expected type `u32`")
+               .span_help(Span { lo: BytePos(16), ..sp }, "declared here")
+               .emit();
+        handler.emit(&MultiSpan::new(), "no location", Level::Warning);
+
+        let output = String::from_utf8(data.lock().unwrap().clone()).unwrap();
+        assert_eq!(output,
+                   "foo.rs:2:18: error[E0308]: mismatched types; note: expected type `u32`; \
+                    help: declared here (at foo.rs:2:5)\n\
+                    warning: no location\n");
+    }
+}
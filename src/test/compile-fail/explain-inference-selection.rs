@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z explain-inference
+
+// Types inferred by projection name the obligation being proven.
+
+fn first<I: Iterator>(mut i: I) -> I::Item {
+    i.next().unwrap()
+}
+
+fn main() {
+    let x = first(vec![1u8].into_iter());
+    //~^ NOTE while proving `<std::vec::IntoIter<u8> as std::iter::Iterator>::Item == u8`
+    let s: String = x;
+    //~^ ERROR mismatched types
+    //~| expected type `std::string::String`
+    //~| found type `u8`
+    //~| expected struct `std::string::String`, found u8
+}
@@ -0,0 +1,21 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z explain-inference
+
+fn main() {
+    let mut v = Vec::new();
+    v.push(1u8); //~ NOTE the type `u8` was inferred here
+    let s: Vec<String> = v;
+    //~^ ERROR mismatched types
+    //~| expected type `std::vec::Vec<std::string::String>`
+    //~| found type `std::vec::Vec<u8>`
+    //~| expected struct `std::string::String`, found u8
+}
@@ -0,0 +1,55 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+#![deny(unused_must_use)]
+#![allow(dead_code)]
+
+struct MyStruct {
+    n: usize
+}
+
+impl MyStruct {
+    #[must_use]
+    fn need_to_use_this_method_value(&self) -> usize {
+        self.n
+    }
+}
+
+trait EvenNature {
+    #[must_use = "no side effects"]
+    fn is_even(&self) -> bool;
+}
+
+impl EvenNature for MyStruct {
+    fn is_even(&self) -> bool {
+        self.n % 2 == 0
+    }
+}
+
+#[must_use = "it's important"]
+fn need_to_use_this_value() -> bool {
+    false
+}
+
+fn main() {
+    need_to_use_this_value(); //~ ERROR unused return value of `need_to_use_this_value` which must be used: it's important
+
+    let m = MyStruct { n: 2 };
+    m.need_to_use_this_method_value(); //~ ERROR which must be used
+    m.is_even(); //~ ERROR which must be used: no side effects
+
+    "  foo  ".trim(); //~ ERROR which must be used: this returns the trimmed string
+    1u8.wrapping_add(1); //~ ERROR which must be used
+
+    let _ = need_to_use_this_value();
+    let _ = m.is_even();
+}
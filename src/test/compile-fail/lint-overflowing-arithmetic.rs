@@ -0,0 +1,36 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(overflowing_arithmetic)]
+#![allow(unused_variables, const_err)]
+
+fn main() {
+    let a = 200u8;
+    let b = a + 100; //~ ERROR attempted to add with overflow
+    let c = a * 2 - 1; //~ ERROR attempted to multiply with overflow
+
+    let zero = 0i32;
+    let d = 1 / zero; //~ ERROR attempted to divide by zero
+    let e = 1 % zero; //~ ERROR attempted to calculate the remainder with a divisor of zero
+
+    let bits = 9;
+    let f = 1u8 << bits; //~ ERROR shift
+
+    let min = -128i8;
+    let g = -min; //~ ERROR attempted to negate with overflow
+
+    // Not constant.
+    let mut m = 200u8;
+    m = 1;
+    let h = m + 100;
+
+    // Fine.
+    let i = a + 55;
+}
@@ -0,0 +1,56 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(perf)]
+#![allow(dead_code, unused_variables)]
+
+fn take_str(s: &str) {}
+fn take_string(s: String) {}
+
+fn clone_on_copy<T: Copy>(x: u32, t: T, r: &(u8, char)) {
+    let a = x.clone(); //~ ERROR using `clone` on a `Copy` type
+    let b = t.clone(); //~ ERROR using `clone` on a `Copy` type
+    let c = r.clone(); //~ ERROR using `clone` on a `Copy` type
+}
+
+fn redundant_clone() {
+    let s = String::new();
+    take_string(s.clone()); //~ ERROR redundant clone
+
+    // Used afterwards.
+    let s = String::new();
+    take_string(s.clone());
+    take_str(&s);
+
+    // Borrowed before the clone.
+    let s = String::new();
+    let t = &s;
+    take_string(s.clone());
+    take_str(t);
+
+    // Cloned in a loop.
+    let s = String::new();
+    for _ in 0..2 {
+        take_string(s.clone());
+    }
+
+    // Cloned through a reference.
+    let s = &String::new();
+    take_string(s.clone());
+}
+
+fn needless_borrow(s: &str) {
+    take_str(&s); //~ ERROR this expression borrows a reference
+    take_str(s);
+    let owned = String::new();
+    take_str(&owned);
+}
+
+fn main() {}
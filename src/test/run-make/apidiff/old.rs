@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub fn foo(x: u32) -> u32 { x }
+
+pub struct Bar {
+    pub a: u32,
+    b: u32,
+}
+
+// Impls count wherever they are written.
+mod imp {
+    impl Clone for super::Bar {
+        fn clone(&self) -> super::Bar {
+            super::Bar { a: self.a, b: self.b }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![warn(clone_on_copy, needless_borrow)]
+
+#[deprecated(since = "1.0.0", suggestion = "new_len")]
+fn old_len(s: &str) -> usize { s.len() }
+
+fn new_len(s: &str) -> usize { s.len() }
+
+fn main() {
+    let s = "foo";
+    let r = &s;
+    let n = old_len(&r);
+    let m: u32 = 1;
+    let k = m.clone() as usize;
+    assert_eq!(n, k + 2);
+}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct NotClone;
+
+#[derive(Clone)]
+struct Wrapper<T>(T);
+
+fn needs_clone<T: Clone>(_: T) {}
+
+fn check() {
+    needs_clone(Wrapper(NotClone));
+}
+
+fn main() {
+    check();
+}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![deny(variant_size_differences)]
+
+// The largest variant is 8 times larger than the next largest.
+pub enum Small {
+    A(u64, u64, u64, u64),
+    B(u32),
+}
+
+// The largest variant is 32 times larger than the next largest.
+pub enum Large {
+    A([u64; 16]),
+    B(u32),
+}
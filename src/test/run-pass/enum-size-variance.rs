@@ -21,7 +21,7 @@ enum Enum3 { D(isize), E, F }
 
 enum Enum4 { H(isize), I(isize), J }
 
-enum Enum5 { //~ ERROR three times larger
+enum Enum5 { //~ ERROR 3 times larger
     L(isize, isize, isize, isize), //~ NOTE this variant is the largest
     M(isize),
     N
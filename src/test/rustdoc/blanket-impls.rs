@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:rustdoc-blanket-impls.rs
+// ignore-cross-compile
+
+extern crate rustdoc_blanket_impls;
+
+pub use rustdoc_blanket_impls::{Shout, Whisper};
+
+// @has blanket_impls/struct.Foo.html '//*[@id="blanket-implementations"]' \
+//      'Blanket Implementations'
+// @has - '//*[@class="impl"]//code' 'Shout for T'
+// @!has - '//*[@class="impl"]//code' 'Whisper for T'
+#[derive(Clone)]
+pub struct Foo;
+
+// @!has blanket_impls/struct.Bar.html '//*[@id="blanket-implementations"]' \
+//       'Blanket Implementations'
+pub struct Bar;
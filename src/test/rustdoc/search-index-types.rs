@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+// The search index describes the types of the arguments and return value of
+// functions, along with their type parameters, which may be nested.
+
+// @has search-index.js '{"generics":[{"generics":[{"name":"u8"}],"name":"vec"}],"name":"option"}'
+// @has - '{"generics":[{"name":"str"}],"name":"slice"}'
+pub fn nested(_: Option<Vec<u8>>, _: &[&str]) -> usize { 0 }
+
+// `-> ()` is described the same as no return type.
+// @has - '"inputs":[{"name":"u32"}],"output":null'
+pub fn unit(_: u32) -> () {}
@@ -0,0 +1,673 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The actual comparison of the two crates.
+//!
+//! The public API of each crate is recorded as an `Api` by the session which
+//! loaded it, and the two are compared afterwards. Items are matched up by
+//! their public path. Types are recorded by printing them with absolute
+//! paths: both crates have the same name in their metadata, so a type
+//! mentioned by the old crate prints the same as its counterpart in the new
+//! one.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::middle::cstore::{self, ChildItem};
+use rustc::ty::{self, TyCtxt};
+use rustc::ty::item_path::with_forced_absolute_paths;
+use rustc::ty::subst::SelfSpace;
+use syntax::ast;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Breaking => write!(f, "breaking"),
+            Severity::NonBreaking => write!(f, "non-breaking"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Change {
+    pub severity: Severity,
+    pub path: String,
+    pub description: String,
+}
+
+pub struct Report {
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|c| c.severity == Severity::Breaking)
+    }
+
+    pub fn print(&self) {
+        for change in &self.changes {
+            println!("{}: {}: {}", change.severity, change.path, change.description);
+        }
+        let bump = if self.is_breaking() {
+            "major"
+        } else if !self.changes.is_empty() {
+            "minor"
+        } else {
+            "patch"
+        };
+        println!("{} change(s) found, suggested version bump: {}", self.changes.len(), bump);
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Namespace {
+    Type,
+    Value,
+}
+
+/// The public items of a crate, keyed by the path they are reachable at.
+struct Exports {
+    items: BTreeMap<(Namespace, String), Def>,
+    impls: Vec<DefId>,
+    /// Every type reachable through `items`, to tell impls on public types
+    /// from impls on private ones.
+    types: HashSet<DefId>,
+}
+
+/// The public API of a crate, as far as it is compared. It holds nothing
+/// which belongs to the session it was recorded by.
+pub struct Api {
+    items: BTreeMap<(Namespace, String), Item>,
+    /// The headers of the trait impls involving public types.
+    impls: BTreeSet<String>,
+}
+
+enum Item {
+    Module,
+    Fn(Signature),
+    Const(Signature),
+    TyAlias(Signature),
+    Static(bool /* is_mutbl */, Signature),
+    Struct(Struct),
+    Enum(Enum),
+    Trait(Trait),
+}
+
+impl Item {
+    fn kind(&self) -> &'static str {
+        match *self {
+            Item::Module => "module",
+            Item::Fn(..) => "function",
+            Item::Const(..) => "constant",
+            Item::TyAlias(..) => "type alias",
+            Item::Static(..) => "static",
+            Item::Struct(..) => "struct",
+            Item::Enum(..) => "enum",
+            Item::Trait(..) => "trait",
+        }
+    }
+}
+
+/// The declared type of an item, e.g. the signature of a function or the
+/// type of a constant.
+struct Signature {
+    ty: String,
+    generics: Generics,
+}
+
+struct Generics {
+    regions: usize,
+    types: Vec<TypeParam>,
+    predicates: BTreeSet<String>,
+}
+
+struct TypeParam {
+    name: String,
+    has_default: bool,
+}
+
+struct Struct {
+    generics: Generics,
+    variant: Variant,
+    /// Public methods and associated constants of the inherent impls, along
+    /// with their signatures.
+    inherent_items: BTreeMap<String, String>,
+}
+
+struct Enum {
+    generics: Generics,
+    variants: BTreeMap<String, Variant>,
+    inherent_items: BTreeMap<String, String>,
+}
+
+struct Variant {
+    kind: ty::VariantKind,
+    fields: Vec<Field>,
+}
+
+impl Variant {
+    fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    public: bool,
+}
+
+struct Trait {
+    generics: Generics,
+    super_predicates: BTreeSet<String>,
+    items: BTreeMap<String, TraitItem>,
+}
+
+/// Records the public API of the crate loaded as `name` by the stub crate.
+/// Returns `None` if it can't be found.
+pub fn collect_api<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, name: &str) -> Option<Api> {
+    let cnum = match find_crate(tcx, name) {
+        Some(cnum) => cnum,
+        None => return None,
+    };
+
+    with_forced_absolute_paths(|| {
+        let exports = collect_exports(tcx, cnum);
+        let items = exports.items.iter().map(|(key, &def)| {
+            (key.clone(), item(tcx, def))
+        }).collect();
+        Some(Api { items: items, impls: trait_impls(tcx, &exports) })
+    })
+}
+
+/// Compares the API of the old version of a crate with the new one.
+pub fn compare(old: &Api, new: &Api) -> Report {
+    let mut cx = DiffCx { changes: Vec::new() };
+    for (key, old_item) in &old.items {
+        match new.items.get(key) {
+            Some(new_item) => cx.compare_items(&key.1, old_item, new_item),
+            None => cx.breaking(&key.1, format!("{} removed", old_item.kind())),
+        }
+    }
+    for (key, new_item) in &new.items {
+        if !old.items.contains_key(key) {
+            cx.non_breaking(&key.1, format!("{} added", new_item.kind()));
+        }
+    }
+    cx.compare_impls(&old.impls, &new.impls);
+
+    let mut changes = cx.changes;
+    changes.sort();
+    Report { changes: changes }
+}
+
+fn find_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, name: &str) -> Option<ast::CrateNum> {
+    tcx.sess.cstore.crates().into_iter().find(|&cnum| {
+        match tcx.sess.cstore.extern_crate(cnum) {
+            Some(ref krate) if krate.direct => &*tcx.item_name(krate.def_id).as_str() == name,
+            _ => false,
+        }
+    })
+}
+
+fn collect_exports<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, cnum: ast::CrateNum) -> Exports {
+    let mut exports = Exports {
+        items: BTreeMap::new(),
+        impls: Vec::new(),
+        types: HashSet::new(),
+    };
+    let mut visited = HashSet::new();
+    let children = tcx.sess.cstore.crate_top_level_items(cnum);
+    collect_children(tcx, children.clone(), "", &mut exports, &mut visited);
+    let mut visited = HashSet::new();
+    collect_impls(tcx, cnum, children, &mut exports.impls, &mut visited);
+    exports
+}
+
+/// Collects the impls of all modules of the crate, including private ones:
+/// an impl is part of the API wherever it is written.
+fn collect_impls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           cnum: ast::CrateNum,
+                           children: Vec<ChildItem>,
+                           impls: &mut Vec<DefId>,
+                           visited: &mut HashSet<DefId>) {
+    for child in children {
+        match child.def {
+            cstore::DlImpl(did) => impls.push(did),
+            // Modules re-exported from other crates are not ours.
+            cstore::DlDef(Def::Mod(did)) if did.krate == cnum => {
+                if visited.insert(did) {
+                    let children = tcx.sess.cstore.item_children(did);
+                    collect_impls(tcx, cnum, children, impls, visited);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_children<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              children: Vec<ChildItem>,
+                              prefix: &str,
+                              exports: &mut Exports,
+                              visited: &mut HashSet<DefId>) {
+    for child in children {
+        let def = match child.def {
+            cstore::DlDef(def) => def,
+            // See `collect_impls`.
+            cstore::DlImpl(..) | cstore::DlField => continue,
+        };
+        if child.vis != ty::Visibility::Public {
+            continue
+        }
+        let path = if prefix.is_empty() {
+            child.name.to_string()
+        } else {
+            format!("{}::{}", prefix, child.name)
+        };
+        let ns = match def {
+            Def::Mod(..) | Def::ForeignMod(..) | Def::Enum(..) | Def::TyAlias(..) |
+            Def::Trait(..) => Namespace::Type,
+            Def::Fn(..) | Def::Const(..) | Def::Static(..) => Namespace::Value,
+            // The constructor of a tuple struct is covered by comparing the
+            // fields of the struct itself.
+            Def::Struct(did) => {
+                if tcx.sess.cstore.tuple_struct_definition_if_ctor(did).is_some() {
+                    continue
+                }
+                Namespace::Type
+            }
+            _ => continue,
+        };
+        exports.items.insert((ns, path.clone()), def);
+
+        match def {
+            Def::Mod(did) => {
+                if visited.insert(did) {
+                    let children = tcx.sess.cstore.item_children(did);
+                    collect_children(tcx, children, &path, exports, visited);
+                }
+            }
+            Def::Struct(did) | Def::Enum(did) => {
+                exports.types.insert(did);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn item<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def: Def) -> Item {
+    match def {
+        Def::Mod(..) | Def::ForeignMod(..) => Item::Module,
+        Def::Fn(did) => Item::Fn(signature(tcx, did)),
+        Def::Const(did) => Item::Const(signature(tcx, did)),
+        Def::TyAlias(did) => Item::TyAlias(signature(tcx, did)),
+        Def::Static(did, mutbl) => Item::Static(mutbl, signature(tcx, did)),
+        Def::Struct(did) => {
+            Item::Struct(Struct {
+                generics: item_generics(tcx, did),
+                variant: variant(tcx.lookup_adt_def(did).struct_variant()),
+                inherent_items: inherent_items(tcx, did),
+            })
+        }
+        Def::Enum(did) => {
+            let variants = tcx.lookup_adt_def(did).variants.iter().map(|v| {
+                (v.name.to_string(), variant(v))
+            }).collect();
+            Item::Enum(Enum {
+                generics: item_generics(tcx, did),
+                variants: variants,
+                inherent_items: inherent_items(tcx, did),
+            })
+        }
+        Def::Trait(did) => {
+            Item::Trait(Trait {
+                generics: generics(&tcx.lookup_trait_def(did).generics, predicates(tcx, did)),
+                super_predicates: super_predicates(tcx, did),
+                items: trait_items(tcx, did),
+            })
+        }
+        // `collect_children` keeps nothing else.
+        _ => unreachable!(),
+    }
+}
+
+fn signature<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, did: DefId) -> Signature {
+    Signature {
+        ty: tcx.lookup_item_type(did).ty.to_string(),
+        generics: item_generics(tcx, did),
+    }
+}
+
+fn item_generics<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, did: DefId) -> Generics {
+    generics(&tcx.lookup_item_type(did).generics, predicates(tcx, did))
+}
+
+fn generics<'tcx>(generics: &ty::Generics<'tcx>, predicates: BTreeSet<String>) -> Generics {
+    // Functions declare their parameters in `FnSpace`, types and traits in
+    // `TypeSpace`; the `Self` of traits is never interesting.
+    Generics {
+        regions: generics.regions.iter().filter(|r| r.space != SelfSpace).count(),
+        types: generics.types.iter().filter(|t| t.space != SelfSpace).map(|t| {
+            TypeParam { name: t.name.to_string(), has_default: t.default.is_some() }
+        }).collect(),
+        predicates: predicates,
+    }
+}
+
+fn variant(variant: ty::VariantDef) -> Variant {
+    Variant {
+        kind: variant.kind(),
+        fields: variant.fields.iter().map(|f| {
+            Field {
+                name: f.name.to_string(),
+                ty: f.unsubst_ty().to_string(),
+                public: f.vis == ty::Visibility::Public,
+            }
+        }).collect(),
+    }
+}
+
+struct DiffCx {
+    changes: Vec<Change>,
+}
+
+impl DiffCx {
+    fn breaking(&mut self, path: &str, description: String) {
+        self.changes.push(Change {
+            severity: Severity::Breaking,
+            path: path.to_string(),
+            description: description,
+        });
+    }
+
+    fn non_breaking(&mut self, path: &str, description: String) {
+        self.changes.push(Change {
+            severity: Severity::NonBreaking,
+            path: path.to_string(),
+            description: description,
+        });
+    }
+
+    fn compare_items(&mut self, path: &str, old: &Item, new: &Item) {
+        match (old, new) {
+            (&Item::Module, &Item::Module) => {}
+            (&Item::Fn(ref old), &Item::Fn(ref new)) |
+            (&Item::Const(ref old), &Item::Const(ref new)) |
+            (&Item::TyAlias(ref old), &Item::TyAlias(ref new)) => {
+                self.compare_signatures(path, old, new);
+            }
+            (&Item::Static(old_mutbl, ref old), &Item::Static(new_mutbl, ref new)) => {
+                if old_mutbl != new_mutbl {
+                    self.breaking(path, "mutability changed".to_string());
+                }
+                self.compare_signatures(path, old, new);
+            }
+            (&Item::Struct(ref old), &Item::Struct(ref new)) => {
+                self.compare_generics(path, &old.generics, &new.generics);
+                self.compare_variant(path, &old.variant, &new.variant);
+                self.compare_inherent_items(path, &old.inherent_items, &new.inherent_items);
+            }
+            (&Item::Enum(ref old), &Item::Enum(ref new)) => {
+                self.compare_generics(path, &old.generics, &new.generics);
+                self.compare_enums(path, old, new);
+                self.compare_inherent_items(path, &old.inherent_items, &new.inherent_items);
+            }
+            (&Item::Trait(ref old), &Item::Trait(ref new)) => {
+                self.compare_traits(path, old, new);
+            }
+            _ => {
+                self.breaking(path, format!("changed from a {} to a {}", old.kind(), new.kind()));
+            }
+        }
+    }
+
+    fn compare_signatures(&mut self, path: &str, old: &Signature, new: &Signature) {
+        if old.ty != new.ty {
+            self.breaking(path, format!("type changed from `{}` to `{}`", old.ty, new.ty));
+        }
+        self.compare_generics(path, &old.generics, &new.generics);
+    }
+
+    fn compare_generics(&mut self, path: &str, old: &Generics, new: &Generics) {
+        if old.regions != new.regions {
+            self.breaking(path, format!("number of lifetime parameters changed from {} to {}",
+                                        old.regions, new.regions));
+        }
+
+        if new.types.len() < old.types.len() {
+            self.breaking(path, format!("number of type parameters changed from {} to {}",
+                                        old.types.len(), new.types.len()));
+        }
+        for param in new.types.iter().skip(old.types.len()) {
+            if param.has_default {
+                self.non_breaking(path, format!("type parameter `{}` added with a default",
+                                                param.name));
+            } else {
+                self.breaking(path, format!("type parameter `{}` added", param.name));
+            }
+        }
+
+        self.compare_predicates(path, &old.predicates, &new.predicates);
+    }
+
+    fn compare_predicates(&mut self,
+                          path: &str,
+                          old: &BTreeSet<String>,
+                          new: &BTreeSet<String>) {
+        for bound in new.difference(old) {
+            self.breaking(path, format!("bound `{}` added", bound));
+        }
+        for bound in old.difference(new) {
+            self.non_breaking(path, format!("bound `{}` removed", bound));
+        }
+    }
+
+    fn compare_variant(&mut self, path: &str, old: &Variant, new: &Variant) {
+        if old.kind != new.kind {
+            self.breaking(path, format!("kind changed from {:?} to {:?}", old.kind, new.kind));
+            return
+        }
+        for old_field in old.fields.iter().filter(|f| f.public) {
+            let field_path = format!("{}::{}", path, old_field.name);
+            match new.field(&old_field.name) {
+                Some(new_field) if new_field.public => {
+                    if old_field.ty != new_field.ty {
+                        self.breaking(&field_path, format!("type changed from `{}` to `{}`",
+                                                           old_field.ty, new_field.ty));
+                    }
+                }
+                _ => self.breaking(&field_path, "public field removed".to_string()),
+            }
+        }
+
+        // If all fields used to be public the type could be constructed
+        // with a literal, which adding any field breaks.
+        let all_public = old.fields.iter().all(|f| f.public);
+        for new_field in &new.fields {
+            if old.field(&new_field.name).is_some() {
+                continue
+            }
+            let field_path = format!("{}::{}", path, new_field.name);
+            if all_public {
+                self.breaking(&field_path, "field added".to_string());
+            } else if new_field.public {
+                self.non_breaking(&field_path, "public field added".to_string());
+            }
+        }
+    }
+
+    fn compare_enums(&mut self, path: &str, old: &Enum, new: &Enum) {
+        for (name, old_variant) in &old.variants {
+            let variant_path = format!("{}::{}", path, name);
+            match new.variants.get(name) {
+                Some(new_variant) => self.compare_variant(&variant_path, old_variant, new_variant),
+                None => self.breaking(&variant_path, "variant removed".to_string()),
+            }
+        }
+        for name in new.variants.keys() {
+            if !old.variants.contains_key(name) {
+                let variant_path = format!("{}::{}", path, name);
+                self.breaking(&variant_path, "variant added".to_string());
+            }
+        }
+    }
+
+    fn compare_traits(&mut self, path: &str, old: &Trait, new: &Trait) {
+        self.compare_generics(path, &old.generics, &new.generics);
+        self.compare_predicates(path, &old.super_predicates, &new.super_predicates);
+
+        for (name, old_item) in &old.items {
+            let item_path = format!("{}::{}", path, name);
+            match new.items.get(name) {
+                Some(new_item) => {
+                    if old_item.signature != new_item.signature {
+                        self.breaking(&item_path, format!("signature changed from `{}` to `{}`",
+                                                          old_item.signature,
+                                                          new_item.signature));
+                    }
+                    if old_item.provided && !new_item.provided {
+                        self.breaking(&item_path, "default removed".to_string());
+                    }
+                }
+                None => self.breaking(&item_path, "trait item removed".to_string()),
+            }
+        }
+        for (name, new_item) in &new.items {
+            if old.items.contains_key(name) {
+                continue
+            }
+            let item_path = format!("{}::{}", path, name);
+            if new_item.provided {
+                self.non_breaking(&item_path, "provided trait item added".to_string());
+            } else {
+                self.breaking(&item_path, "required trait item added".to_string());
+            }
+        }
+    }
+
+    fn compare_inherent_items(&mut self,
+                              path: &str,
+                              old: &BTreeMap<String, String>,
+                              new: &BTreeMap<String, String>) {
+        for (name, old_sig) in old {
+            let item_path = format!("{}::{}", path, name);
+            match new.get(name) {
+                Some(new_sig) if old_sig != new_sig => {
+                    self.breaking(&item_path, format!("signature changed from `{}` to `{}`",
+                                                      old_sig, new_sig));
+                }
+                Some(_) => {}
+                None => self.breaking(&item_path, "method removed".to_string()),
+            }
+        }
+        for name in new.keys() {
+            if !old.contains_key(name) {
+                self.non_breaking(&format!("{}::{}", path, name), "method added".to_string());
+            }
+        }
+    }
+
+    /// Trait impls are matched up by their printed header, as they have no
+    /// path of their own.
+    fn compare_impls(&mut self, old: &BTreeSet<String>, new: &BTreeSet<String>) {
+        for header in old.difference(new) {
+            self.breaking(header, "trait impl removed".to_string());
+        }
+        for header in new.difference(old) {
+            self.non_breaking(header, "trait impl added".to_string());
+        }
+    }
+}
+
+fn predicates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, did: DefId) -> BTreeSet<String> {
+    tcx.lookup_predicates(did).predicates.iter().map(|p| p.to_string()).collect()
+}
+
+fn super_predicates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, did: DefId) -> BTreeSet<String> {
+    tcx.lookup_super_predicates(did).predicates.iter().map(|p| p.to_string()).collect()
+}
+
+struct TraitItem {
+    signature: String,
+    provided: bool,
+}
+
+fn trait_items<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                         did: DefId) -> BTreeMap<String, TraitItem> {
+    let provided: HashSet<_> = tcx.provided_trait_methods(did).iter().map(|m| m.name).collect();
+    tcx.trait_items(did).iter().map(|item| {
+        let (signature, provided) = match *item {
+            ty::MethodTraitItem(ref m) => {
+                let bounds = predicates(tcx, m.def_id).into_iter().collect::<Vec<_>>();
+                let signature = if bounds.is_empty() {
+                    m.fty.to_string()
+                } else {
+                    format!("{} where {}", m.fty, bounds.join(", "))
+                };
+                (signature, provided.contains(&m.name))
+            }
+            ty::ConstTraitItem(ref c) => (format!("const: {}", c.ty), c.has_value),
+            ty::TypeTraitItem(ref t) => ("type".to_string(), t.ty.is_some()),
+        };
+        (item.name().to_string(), TraitItem { signature: signature, provided: provided })
+    }).collect()
+}
+
+/// Public methods and associated constants of the inherent impls of a type,
+/// along with their signatures.
+fn inherent_items<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            did: DefId) -> BTreeMap<String, String> {
+    let mut items = BTreeMap::new();
+    for impl_did in tcx.sess.cstore.inherent_implementations_for_type(did) {
+        for item_id in tcx.sess.cstore.impl_items(impl_did) {
+            let signature = match tcx.impl_or_trait_item(item_id.def_id()) {
+                ty::MethodTraitItem(ref m) if m.vis == ty::Visibility::Public => {
+                    m.fty.to_string()
+                }
+                ty::ConstTraitItem(ref c) if c.vis == ty::Visibility::Public => {
+                    format!("const: {}", c.ty)
+                }
+                _ => continue,
+            };
+            let name = tcx.sess.cstore.item_name(item_id.def_id()).to_string();
+            items.insert(name, signature);
+        }
+    }
+    items
+}
+
+/// The headers of all trait impls of a crate involving public types.
+fn trait_impls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, exports: &Exports) -> BTreeSet<String> {
+    exports.impls.iter().filter_map(|&did| {
+        let trait_ref = match tcx.impl_trait_ref(did) {
+            Some(trait_ref) => trait_ref,
+            None => return None,
+        };
+        if tcx.sess.cstore.is_default_impl(did) {
+            return None
+        }
+        match trait_ref.self_ty().sty {
+            ty::TyStruct(def, _) | ty::TyEnum(def, _) if !exports.types.contains(&def.did) => {
+                return None
+            }
+            _ => {}
+        }
+        Some(format!("impl {} for {}", trait_ref, trait_ref.self_ty()))
+    }).collect()
+}
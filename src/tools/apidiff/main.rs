@@ -0,0 +1,156 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compares the public API of two compiled versions of a library.
+//!
+//! Usage: `apidiff [-L PATH]... OLD.rlib NEW.rlib`
+//!
+//! Each library is loaded by a compiler session of its own, as `old` or
+//! `new`, by compiling a stub crate which contains nothing but
+//! `extern crate old;` (or `new`): two versions of a library usually have the
+//! same crate name, and two different crates of the same name can't be loaded
+//! into one session. Once analysis has finished the public API of the crate
+//! is recorded from its metadata. The two APIs are then compared and every
+//! difference is reported, classified as either breaking or non-breaking.
+//!
+//! The exit status is 0 if no breaking change was found, 1 if there was at
+//! least one, and 101 if the libraries could not be loaded.
+
+#![feature(rustc_private)]
+#![feature(question_mark)]
+
+extern crate getopts;
+extern crate rustc;
+extern crate rustc_driver;
+extern crate syntax;
+
+use std::cell::RefCell;
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use std::rc::Rc;
+
+use rustc::session::Session;
+use rustc::session::config::{self, Input};
+use rustc_driver::{driver, CompilerCalls, Compilation};
+use rustc_driver::driver::CompileController;
+use syntax::diagnostics;
+
+mod diff;
+
+use diff::Api;
+
+struct ApidiffCalls {
+    /// The name the library is loaded as.
+    name: String,
+    api: Rc<RefCell<Option<Api>>>,
+}
+
+impl<'a> CompilerCalls<'a> for ApidiffCalls {
+    fn no_input(&mut self,
+                _: &getopts::Matches,
+                _: &config::Options,
+                _: &Option<PathBuf>,
+                _: &Option<PathBuf>,
+                _: &diagnostics::registry::Registry)
+                -> Option<(Input, Option<PathBuf>)> {
+        let input = Input::Str {
+            name: "apidiff".to_string(),
+            input: format!("extern crate {};", self.name),
+        };
+        Some((input, None))
+    }
+
+    fn build_controller(&mut self,
+                        _: &Session,
+                        _: &getopts::Matches)
+                        -> CompileController<'a> {
+        let name = self.name.clone();
+        let api = self.api.clone();
+        let mut control = CompileController::basic();
+        control.after_analysis.stop = Compilation::Stop;
+        control.after_analysis.callback = Box::new(move |state: &mut driver::CompileState| {
+            let tcx = state.tcx.unwrap();
+            *api.borrow_mut() = diff::collect_api(tcx, &name);
+        });
+        control
+    }
+}
+
+fn usage(program: &str) -> String {
+    format!("Usage: {} [-L PATH]... OLD NEW\n\n\
+             Compares the public API of the compiled libraries OLD and NEW.",
+            program)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut opts = getopts::Options::new();
+    opts.optmulti("L", "", "add a directory to the library search path", "PATH");
+    opts.optopt("", "sysroot", "override the system root", "PATH");
+    opts.optflag("h", "help", "display this message");
+
+    let matches = match opts.parse(&args[1..]) {
+        Ok(m) => m,
+        Err(e) => {
+            println!("{}\n\n{}", e, opts.usage(&usage(&args[0])));
+            process::exit(101);
+        }
+    };
+    if matches.opt_present("h") || matches.free.len() != 2 {
+        println!("{}", opts.usage(&usage(&args[0])));
+        process::exit(if matches.opt_present("h") { 0 } else { 101 });
+    }
+
+    let old = load_api(&args[0], &matches, "old", &matches.free[0]);
+    let new = load_api(&args[0], &matches, "new", &matches.free[1]);
+    let report = diff::compare(&old, &new);
+    report.print();
+    if report.is_breaking() {
+        process::exit(1);
+    }
+}
+
+/// Loads the library at `path` as `name` and records its API, exiting if
+/// that fails.
+fn load_api(program: &str, matches: &getopts::Matches, name: &str, path: &str) -> Api {
+    let mut rustc_args = vec![
+        program.to_string(),
+        "--crate-name".to_string(), "apidiff".to_string(),
+        "--crate-type".to_string(), "lib".to_string(),
+        "--extern".to_string(), format!("{}={}", name, path),
+    ];
+    for path in matches.opt_strs("L") {
+        rustc_args.push("-L".to_string());
+        rustc_args.push(path);
+    }
+    if let Some(sysroot) = matches.opt_str("sysroot") {
+        rustc_args.push("--sysroot".to_string());
+        rustc_args.push(sysroot);
+    }
+
+    let mut calls = ApidiffCalls {
+        name: name.to_string(),
+        api: Rc::new(RefCell::new(None)),
+    };
+    let (result, _) = rustc_driver::run_compiler(&rustc_args, &mut calls);
+    if result.is_err() {
+        process::exit(101);
+    }
+
+    let api = calls.api.borrow_mut().take();
+    match api {
+        Some(api) => api,
+        None => {
+            println!("error: could not find the crate `{}`", name);
+            process::exit(101);
+        }
+    }
+}